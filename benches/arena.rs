@@ -0,0 +1,91 @@
+// Benchmarks comparing arena::ArenaList against the Box based solution::List.
+//
+// Each benchmark builds a list by pushing LEN elements, walks it, or pushes and
+// pops in a loop so that the ArenaList can reuse its slots.
+//
+// Run them with `cargo bench --bench arena`.
+
+use rust101::arena::ArenaList;
+use rust101::solution::List;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const LEN: u32 = 1_000_000;
+const RUNS: u32 = 20;
+
+/// Runs `f` RUNS times and prints the median time.
+fn bench<R, F: FnMut() -> R>(name: &str, mut f: F) {
+    let mut times: Vec<Duration> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            let result = black_box(f());
+            let time = start.elapsed();
+            drop(result);
+            time
+        })
+        .collect();
+    times.sort();
+    println!("{:<32} {:>12?}", name, times[times.len() / 2]);
+}
+
+fn main() {
+    bench("push/list", || {
+        let mut list = List::empty();
+        for i in 0..LEN {
+            list.push(black_box(i));
+        }
+        list
+    });
+    bench("push/arena", || {
+        let mut list = ArenaList::empty();
+        for i in 0..LEN {
+            list.push(black_box(i));
+        }
+        list
+    });
+    bench("push/arena_with_capacity", || {
+        let mut list = ArenaList::with_capacity(LEN as usize);
+        for i in 0..LEN {
+            list.push(black_box(i));
+        }
+        list
+    });
+
+    let mut list = List::empty();
+    let mut arena = ArenaList::empty();
+    for i in 0..LEN {
+        list.push(i);
+        arena.push(i);
+    }
+    bench("sum/list", || {
+        (&list).into_iter().map(|&n| n as u64).sum::<u64>()
+    });
+    bench("sum/arena", || {
+        (&arena).into_iter().map(|&n| n as u64).sum::<u64>()
+    });
+
+    bench("push_pop/list", || {
+        for i in 0..LEN {
+            list.push(black_box(i));
+            if i % 4 == 0 {
+                list.pop();
+                list.pop();
+            }
+        }
+        for _ in 0..LEN / 2 {
+            list.pop();
+        }
+    });
+    bench("push_pop/arena", || {
+        for i in 0..LEN {
+            arena.push(black_box(i));
+            if i % 4 == 0 {
+                arena.pop();
+                arena.pop();
+            }
+        }
+        for _ in 0..LEN / 2 {
+            arena.pop();
+        }
+    });
+}
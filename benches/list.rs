@@ -0,0 +1,90 @@
+// Benchmarks for the algorithms on solution::List.
+//
+// These compare working on the List directly against the common workaround of
+// collecting it into a Vec, using the Vec algorithm and building a new List.
+//
+// Run them with `cargo bench`.
+
+use rust101::solution::List;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const LEN: u32 = 100_000;
+const RUNS: u32 = 20;
+
+/// Runs `f` on a fresh copy of `input` RUNS times and prints the median time.
+fn bench<F: FnMut(List<u32>) -> List<u32>>(name: &str, input: &List<u32>, mut f: F) {
+    let mut times: Vec<Duration> = (0..RUNS)
+        .map(|_| {
+            let list = input.clone();
+            let start = Instant::now();
+            let list = black_box(f(black_box(list)));
+            let time = start.elapsed();
+            drop(list);
+            time
+        })
+        .collect();
+    times.sort();
+    println!("{:<32} {:>12?}", name, times[times.len() / 2]);
+}
+
+fn shuffled(len: u32) -> List<u32> {
+    let mut state: u32 = 1;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            state >> 8
+        })
+        .collect()
+}
+
+fn main() {
+    let input = shuffled(LEN);
+
+    bench("sort/list", &input, |mut list| {
+        list.sort();
+        list
+    });
+    bench("sort/vec", &input, |list| {
+        let mut vec: Vec<_> = list.into_iter().collect();
+        vec.sort();
+        vec.into_iter().collect()
+    });
+    bench("sort_unstable/vec", &input, |list| {
+        let mut vec: Vec<_> = list.into_iter().collect();
+        vec.sort_unstable();
+        vec.into_iter().collect()
+    });
+
+    bench("reverse/list", &input, |mut list| {
+        list.reverse();
+        list
+    });
+    bench("reverse/vec", &input, |list| {
+        let mut vec: Vec<_> = list.into_iter().collect();
+        vec.reverse();
+        vec.into_iter().collect()
+    });
+
+    let mut sorted = input.clone();
+    sorted.sort_by_key(|n| n % 1000);
+    bench("dedup_by_key/list", &sorted, |mut list| {
+        list.dedup_by_key(|n| *n % 1000);
+        list
+    });
+    bench("dedup_by_key/vec", &sorted, |list| {
+        let mut vec: Vec<_> = list.into_iter().collect();
+        vec.dedup_by_key(|n| *n % 1000);
+        vec.into_iter().collect()
+    });
+
+    bench("retain/list", &input, |mut list| {
+        list.retain(|n| n % 2 == 0);
+        list
+    });
+    bench("retain/vec", &input, |list| {
+        let mut vec: Vec<_> = list.into_iter().collect();
+        vec.retain(|n| n % 2 == 0);
+        vec.into_iter().collect()
+    });
+}
@@ -0,0 +1,437 @@
+// Arena-backed list.
+//
+// Every push on the Box based List makes a separate heap allocation, and the
+// Nodes end up scattered all over memory. For big lists that is slow to build,
+// slow to walk and fragments the heap. Here all of the Nodes live in a single
+// Vec and link to each other by index. Popped slots are kept on a free list and
+// reused by the next push, so a list that is repeatedly pushed and popped stops
+// allocating once it has reached its largest size.
+//
+// Because the Nodes never move out of their slot we can hand out stable
+// Handles to them. Each slot has a generation that is bumped whenever it is
+// freed, so a Handle to a popped element can't accidentally be used to access
+// whatever element reuses its slot later.
+
+/// Singly linked list storing its Nodes in a Vec.
+///
+/// This has the same API as `solution::List`, but is much cheaper to build
+/// and walk for large lists.
+pub struct ArenaList<T> {
+    slots: Vec<Slot<T>>,
+    head: Option<usize>,
+    free: Option<usize>,
+    len: usize,
+}
+
+struct Slot<T> {
+    generation: u32,
+    entry: Entry<T>,
+}
+
+enum Entry<T> {
+    Occupied(Node<T>),
+    Vacant { next_free: Option<usize> },
+}
+
+struct Node<T> {
+    val: T,
+    next: Option<usize>,
+}
+
+/// Stable reference to an element of an ArenaList.
+///
+/// A Handle stays valid until its element is popped, no matter how many other
+/// elements are pushed or popped in the meantime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: usize,
+    generation: u32,
+}
+
+impl<T> ArenaList<T> {
+    /// Creates an empty list.
+    pub fn empty() -> Self {
+        ArenaList {
+            slots: Vec::new(),
+            head: None,
+            free: None,
+            len: 0,
+        }
+    }
+
+    /// Creates an empty list with room for `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        ArenaList {
+            slots: Vec::with_capacity(capacity),
+            ..ArenaList::empty()
+        }
+    }
+
+    /// Returns true iff the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Returns the number of elements in the list.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of elements the list can hold without allocating.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    /// Adds a new element to the front of the list and returns a Handle to it.
+    pub fn push(&mut self, element: T) -> Handle {
+        let node = Entry::Occupied(Node {
+            val: element,
+            next: self.head,
+        });
+        let index = match self.free {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                self.free = match slot.entry {
+                    Entry::Vacant { next_free } => next_free,
+                    Entry::Occupied(_) => unreachable!("occupied slot on the free list"),
+                };
+                slot.entry = node;
+                index
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    entry: node,
+                });
+                self.slots.len() - 1
+            }
+        };
+        self.head = Some(index);
+        self.len += 1;
+        Handle {
+            index,
+            generation: self.slots[index].generation,
+        }
+    }
+
+    /// Removes the first element from the list and returns it.
+    pub fn pop(&mut self) -> Option<T> {
+        let index = self.head?;
+        let slot = &mut self.slots[index];
+        let entry = std::mem::replace(
+            &mut slot.entry,
+            Entry::Vacant {
+                next_free: self.free,
+            },
+        );
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free = Some(index);
+        self.len -= 1;
+        match entry {
+            Entry::Occupied(node) => {
+                self.head = node.next;
+                Some(node.val)
+            }
+            Entry::Vacant { .. } => unreachable!("vacant slot in the list"),
+        }
+    }
+
+    /// Returns a Handle to the first element of the list.
+    pub fn front(&self) -> Option<Handle> {
+        self.head.map(|index| self.handle(index))
+    }
+
+    /// Returns a Handle to the element after the one `handle` refers to.
+    ///
+    /// Returns None if `handle` refers to the last element, or to an element
+    /// that has been popped.
+    pub fn next(&self, handle: Handle) -> Option<Handle> {
+        self.node(handle)?.next.map(|index| self.handle(index))
+    }
+
+    /// Returns the element `handle` refers to, or None if it has been popped.
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.node(handle).map(|node| &node.val)
+    }
+
+    /// Returns the element `handle` refers to, or None if it has been popped.
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        match self.slots.get_mut(handle.index) {
+            Some(Slot {
+                generation,
+                entry: Entry::Occupied(node),
+            }) if *generation == handle.generation => Some(&mut node.val),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over references to the elements.
+    pub fn iter(&self) -> ReferenceIter<'_, T> {
+        self.into_iter()
+    }
+
+    /// Returns an iterator that allows modifying each element.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.into_iter()
+    }
+
+    fn handle(&self, index: usize) -> Handle {
+        Handle {
+            index,
+            generation: self.slots[index].generation,
+        }
+    }
+
+    fn node(&self, handle: Handle) -> Option<&Node<T>> {
+        match self.slots.get(handle.index) {
+            Some(Slot {
+                generation,
+                entry: Entry::Occupied(node),
+            }) if *generation == handle.generation => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Default for ArenaList<T> {
+    fn default() -> Self {
+        ArenaList::empty()
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for ArenaList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+/// Draining iterator for an owned list.
+pub struct DrainingIterator<T>(ArenaList<T>);
+
+impl<T> Iterator for DrainingIterator<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> ExactSizeIterator for DrainingIterator<T> {}
+
+impl<T> std::iter::FusedIterator for DrainingIterator<T> {}
+
+impl<T> IntoIterator for ArenaList<T> {
+    type IntoIter = DrainingIterator<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        DrainingIterator(self)
+    }
+}
+
+/// Reference iterator for a reference to a list.
+pub struct ReferenceIter<'a, T: 'a> {
+    slots: &'a [Slot<T>],
+    current: Option<usize>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for ReferenceIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &self.slots[self.current?].entry {
+            Entry::Occupied(node) => {
+                self.current = node.next;
+                self.len -= 1;
+                Some(&node.val)
+            }
+            Entry::Vacant { .. } => unreachable!("vacant slot in the list"),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for ReferenceIter<'a, T> {}
+
+impl<'a, T> std::iter::FusedIterator for ReferenceIter<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a ArenaList<T> {
+    type Item = &'a T;
+    type IntoIter = ReferenceIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ReferenceIter {
+            slots: &self.slots,
+            current: self.head,
+            len: self.len,
+        }
+    }
+}
+
+/// Mutable reference iterator for a mutable reference to a list.
+//
+// The borrow checker can't see that following the links visits each slot at
+// most once, so we walk them through a raw pointer to the slots instead.
+pub struct IterMut<'a, T: 'a> {
+    slots: *mut Slot<T>,
+    current: Option<usize>,
+    len: usize,
+    marker: std::marker::PhantomData<&'a mut [Slot<T>]>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.current?;
+        // SAFETY: the slots are mutably borrowed for 'a, index is in bounds
+        // since it is linked from the list, and the links never form a cycle
+        // so we hand out each element at most once.
+        match unsafe { &mut (*self.slots.add(index)).entry } {
+            Entry::Occupied(node) => {
+                self.current = node.next;
+                self.len -= 1;
+                Some(&mut node.val)
+            }
+            Entry::Vacant { .. } => unreachable!("vacant slot in the list"),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> std::iter::FusedIterator for IterMut<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a mut ArenaList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IterMut {
+            slots: self.slots.as_mut_ptr(),
+            current: self.head,
+            len: self.len,
+            marker: std::marker::PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+struct NonCopyable(u32);
+
+#[test]
+fn test_empty() {
+    assert!(ArenaList::<i32>::empty().is_empty());
+    assert_eq!(ArenaList::<i32>::default().len(), 0);
+}
+
+#[test]
+fn test_push_pop() {
+    let mut list = ArenaList::empty();
+    list.push(NonCopyable(1));
+    list.push(NonCopyable(2));
+    list.push(NonCopyable(3));
+    assert!(!list.is_empty());
+    assert_eq!(list.len(), 3);
+
+    assert_eq!(list.pop(), Some(NonCopyable(3)));
+    assert_eq!(list.pop(), Some(NonCopyable(2)));
+    list.push(NonCopyable(4));
+    assert_eq!(list.pop(), Some(NonCopyable(4)));
+    assert_eq!(list.pop(), Some(NonCopyable(1)));
+    assert_eq!(list.pop(), None);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn test_iterators() {
+    let mut list = ArenaList::empty();
+    list.push(NonCopyable(1));
+    list.push(NonCopyable(2));
+    list.push(NonCopyable(3));
+    list.pop();
+    list.push(NonCopyable(3));
+
+    let mut iter = list.iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next(), Some(&NonCopyable(3)));
+    assert_eq!(iter.next(), Some(&NonCopyable(2)));
+    assert_eq!(iter.next(), Some(&NonCopyable(1)));
+    assert_eq!(iter.next(), None);
+
+    for (i, n) in list.iter_mut().enumerate() {
+        n.0 += i as u32 * 10;
+    }
+    assert_eq!(
+        format!("{:?}", list),
+        "[NonCopyable(3), NonCopyable(12), NonCopyable(21)]"
+    );
+
+    let mut items = Vec::new();
+    for item in list {
+        items.push(item);
+    }
+    assert_eq!(
+        items,
+        vec![NonCopyable(3), NonCopyable(12), NonCopyable(21)]
+    );
+}
+
+#[test]
+fn test_reuses_slots() {
+    let mut list = ArenaList::with_capacity(2);
+    list.push(1);
+    list.push(2);
+    let capacity = list.capacity();
+    for i in 0..100 {
+        list.pop();
+        list.push(i);
+    }
+    assert_eq!(list.capacity(), capacity);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [99, 1]);
+}
+
+#[test]
+fn test_handles() {
+    let mut list = ArenaList::empty();
+    let one = list.push(NonCopyable(1));
+    let two = list.push(NonCopyable(2));
+    assert_eq!(list.front(), Some(two));
+    assert_eq!(list.next(two), Some(one));
+    assert_eq!(list.next(one), None);
+    assert_eq!(list.get(one), Some(&NonCopyable(1)));
+
+    list.get_mut(one).unwrap().0 = 10;
+    for i in 3..10 {
+        list.push(NonCopyable(i));
+    }
+    assert_eq!(list.get(one), Some(&NonCopyable(10)));
+    assert_eq!(list.get(two), Some(&NonCopyable(2)));
+
+    // Once an element is popped its Handle no longer works, even after the
+    // slot has been reused.
+    while list.len() > 1 {
+        list.pop();
+    }
+    assert_eq!(list.get(two), None);
+    let three = list.push(NonCopyable(3));
+    assert_eq!(list.get(two), None);
+    assert_eq!(list.get_mut(two), None);
+    assert_eq!(list.next(two), None);
+    assert_ne!(three, two);
+    assert_eq!(list.get(three), Some(&NonCopyable(3)));
+    assert_eq!(list.next(three), Some(one));
+}
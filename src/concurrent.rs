@@ -0,0 +1,452 @@
+// Lock-free concurrent stack.
+//
+// This is a Treiber stack: the same singly linked layout as solution::List, but
+// the head is an AtomicPtr and push and pop swing it with compare_exchange so
+// that any number of threads can use the stack through a shared reference.
+//
+// The hard part is freeing popped Nodes. Another thread may have loaded the
+// same head just before we popped it and still be about to read its `next`
+// field, so we can't free the Node right away. Worse, if the Node were freed
+// and its memory reused for a new Node pushed to the stack, that thread's
+// compare_exchange would succeed on a stale head (the ABA problem) and corrupt
+// the stack.
+//
+// We solve both problems with hazard pointers. Before a thread dereferences the
+// head it publishes it in a hazard record and checks that it is still the
+// head. Popped Nodes are not freed but retired to a lock-free list, and every
+// so often that list is scanned and the Nodes that no hazard record protects
+// are freed. A protected Node is never freed, so its address can't be reused
+// while any thread might still compare against it.
+//
+// Run the tests under Miri when changing this, with a few seeds as the races
+// depend on how the threads are scheduled:
+//
+//     MIRIFLAGS=-Zmiri-many-seeds=0..8 cargo +nightly miri test --lib concurrent
+
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+/// How many retired Nodes we collect before trying to free them.
+const SCAN_THRESHOLD: usize = 64;
+
+/// Lock-free stack that can be shared between threads.
+pub struct ConcurrentStack<T> {
+    head: AtomicPtr<Node<T>>,
+    hazards: AtomicPtr<HazardRecord>,
+    retired: AtomicPtr<Node<T>>,
+    retired_len: AtomicUsize,
+}
+
+struct Node<T> {
+    // The value is moved out by pop, long before the Node is freed.
+    val: ManuallyDrop<T>,
+    // Never changes once the Node has been pushed.
+    next: *mut Node<T>,
+    // Links retired Nodes together. Only used once the Node has been popped,
+    // and only by the thread that owns the retired list at the time.
+    retired_next: *mut Node<T>,
+}
+
+/// A slot where one thread at a time can publish the Node it is reading.
+///
+/// Records are only freed when the stack is dropped, so they can be reused by
+/// any thread.
+struct HazardRecord {
+    protected: AtomicPtr<u8>,
+    active: AtomicBool,
+    // Never changes once the record has been added to the list.
+    next: *mut HazardRecord,
+}
+
+/// Clears and releases a HazardRecord when dropped.
+struct HazardGuard<'a> {
+    record: &'a HazardRecord,
+}
+
+impl<'a> HazardGuard<'a> {
+    fn protect<T>(&self, node: *mut Node<T>) {
+        self.record.protected.store(node.cast(), Ordering::SeqCst);
+    }
+}
+
+impl<'a> Drop for HazardGuard<'a> {
+    fn drop(&mut self) {
+        self.record
+            .protected
+            .store(ptr::null_mut(), Ordering::Release);
+        self.record.active.store(false, Ordering::Release);
+    }
+}
+
+impl<T> ConcurrentStack<T> {
+    /// Creates an empty stack.
+    pub fn empty() -> Self {
+        ConcurrentStack {
+            head: AtomicPtr::new(ptr::null_mut()),
+            hazards: AtomicPtr::new(ptr::null_mut()),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            retired_len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns true iff the stack is empty.
+    ///
+    /// Other threads may push or pop at any time, so the answer may already be
+    /// out of date when it is returned.
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+
+    /// Adds a new element to the top of the stack.
+    pub fn push(&self, element: T) {
+        let node = Box::into_raw(Box::new(Node {
+            val: ManuallyDrop::new(element),
+            next: ptr::null_mut(),
+            retired_next: ptr::null_mut(),
+        }));
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            // SAFETY: the Node isn't shared until the compare_exchange below
+            // succeeds.
+            unsafe { (*node).next = head };
+            match self
+                .head
+                .compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed)
+            {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Removes the top element from the stack and returns it.
+    pub fn pop(&self) -> Option<T> {
+        let hazard = self.acquire_hazard();
+        loop {
+            let head = self.head.load(Ordering::Acquire);
+            if head.is_null() {
+                return None;
+            }
+            // Once the head is protected it can't be freed, but it may have
+            // been popped (and even retired) before we protected it, so check
+            // that it is still in the stack.
+            hazard.protect(head);
+            let current = self.head.load(Ordering::SeqCst);
+            if current != head {
+                continue;
+            }
+            // The Node may have been freed before we protected it and a new
+            // one allocated at the same address, so only the pointer we loaded
+            // after protecting it is known to point at a live Node.
+            let head = current;
+            // SAFETY: head is protected so it hasn't been freed, and `next`
+            // never changes after a push.
+            let next = unsafe { (*head).next };
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::SeqCst, Ordering::Relaxed)
+                .is_ok()
+            {
+                drop(hazard);
+                // SAFETY: we popped head, so we are the only thread that will
+                // take its value, and no other thread reads the value.
+                let val = unsafe { ManuallyDrop::take(&mut (*head).val) };
+                self.retire(head);
+                return Some(val);
+            }
+        }
+    }
+
+    /// Finds an inactive HazardRecord, or adds a new one, and claims it.
+    fn acquire_hazard(&self) -> HazardGuard<'_> {
+        let mut current = self.hazards.load(Ordering::Acquire);
+        while !current.is_null() {
+            // SAFETY: records are only freed when the stack is dropped.
+            let record = unsafe { &*current };
+            if !record.active.load(Ordering::Relaxed)
+                && record
+                    .active
+                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            {
+                return HazardGuard { record };
+            }
+            current = record.next;
+        }
+
+        let record = Box::into_raw(Box::new(HazardRecord {
+            protected: AtomicPtr::new(ptr::null_mut()),
+            active: AtomicBool::new(true),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.hazards.load(Ordering::Relaxed);
+        loop {
+            // SAFETY: the record isn't shared until the compare_exchange
+            // below succeeds.
+            unsafe { (*record).next = head };
+            // SeqCst so that a scan which doesn't see this record runs
+            // entirely before it, see the fence in scan.
+            match self.hazards.compare_exchange_weak(
+                head,
+                record,
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            ) {
+                // SAFETY: records are only freed when the stack is dropped.
+                Ok(_) => {
+                    return HazardGuard {
+                        record: unsafe { &*record },
+                    }
+                }
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Adds a popped Node to the retired list, and frees the retired Nodes
+    /// that are safe to free once there are enough of them.
+    fn retire(&self, node: *mut Node<T>) {
+        // Count the Node before it is on the list, otherwise another thread's
+        // scan could free it and decrement the count before we increment it.
+        let len = self.retired_len.fetch_add(1, Ordering::Relaxed) + 1;
+        self.push_retired(node);
+        if len >= SCAN_THRESHOLD {
+            self.scan();
+        }
+    }
+
+    fn push_retired(&self, node: *mut Node<T>) {
+        let mut head = self.retired.load(Ordering::Relaxed);
+        loop {
+            // SAFETY: the Node has been popped, so only we can touch its
+            // retired_next until it is on the retired list.
+            unsafe { (*node).retired_next = head };
+            match self.retired.compare_exchange_weak(
+                head,
+                node,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Frees every retired Node that isn't protected by a HazardRecord.
+    fn scan(&self) {
+        // Take the whole retired list so that concurrent scans never see the
+        // same Nodes.
+        let mut current = self.retired.swap(ptr::null_mut(), Ordering::Acquire);
+        // Pairs with the SeqCst protect and head load in pop: every Node we
+        // took was popped before this fence, so a thread that protected one
+        // of them and still saw it as the head protected it before the fence,
+        // and we see its hazard below. The same goes for the record it
+        // protected it with: a record pushed after the fence can only protect
+        // Nodes that were still on the stack, so the SeqCst push and load of
+        // the record list make sure we walk every record that matters.
+        std::sync::atomic::fence(Ordering::SeqCst);
+
+        let mut protected = Vec::new();
+        let mut record = self.hazards.load(Ordering::SeqCst);
+        while !record.is_null() {
+            // SAFETY: records are only freed when the stack is dropped.
+            let hazard = unsafe { &*record };
+            let node = hazard.protected.load(Ordering::SeqCst);
+            if !node.is_null() {
+                protected.push(node.cast::<Node<T>>());
+            }
+            record = hazard.next;
+        }
+
+        while !current.is_null() {
+            let node = current;
+            // SAFETY: we own all of the Nodes on the list we took.
+            current = unsafe { (*node).retired_next };
+            if protected.contains(&node) {
+                self.push_retired(node);
+            } else {
+                self.retired_len.fetch_sub(1, Ordering::Relaxed);
+                // SAFETY: the Node has been popped and no thread protects it,
+                // so no thread can read it any more. Its value was already
+                // taken and is wrapped in ManuallyDrop so it isn't dropped
+                // again.
+                drop(unsafe { Box::from_raw(node) });
+            }
+        }
+    }
+}
+
+impl<T> Drop for ConcurrentStack<T> {
+    fn drop(&mut self) {
+        // We have exclusive access, so no other thread can be reading any
+        // Node or record.
+        let mut current = *self.head.get_mut();
+        while !current.is_null() {
+            // SAFETY: the Nodes still in the stack own their values.
+            let mut node = unsafe { Box::from_raw(current) };
+            unsafe { ManuallyDrop::drop(&mut node.val) };
+            current = node.next;
+        }
+        let mut current = *self.retired.get_mut();
+        while !current.is_null() {
+            // SAFETY: the values of retired Nodes have already been taken.
+            let node = unsafe { Box::from_raw(current) };
+            current = node.retired_next;
+        }
+        let mut current = *self.hazards.get_mut();
+        while !current.is_null() {
+            // SAFETY: no guard can outlive the borrow of the stack.
+            let record = unsafe { Box::from_raw(current) };
+            current = record.next;
+        }
+    }
+}
+
+// The raw pointers stop the compiler from working out that these are safe.
+// The stack owns its values and hands each one to exactly one thread, which
+// needs T: Send but never shares a &T between threads.
+unsafe impl<T: Send> Send for ConcurrentStack<T> {}
+unsafe impl<T: Send> Sync for ConcurrentStack<T> {}
+
+impl<T> Default for ConcurrentStack<T> {
+    fn default() -> Self {
+        ConcurrentStack::empty()
+    }
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+struct NonCopyable(u32);
+
+#[test]
+fn test_push_pop() {
+    let stack = ConcurrentStack::empty();
+    assert!(stack.is_empty());
+    stack.push(NonCopyable(1));
+    stack.push(NonCopyable(2));
+    stack.push(NonCopyable(3));
+    assert!(!stack.is_empty());
+
+    assert_eq!(stack.pop(), Some(NonCopyable(3)));
+    assert_eq!(stack.pop(), Some(NonCopyable(2)));
+    assert_eq!(stack.pop(), Some(NonCopyable(1)));
+    assert_eq!(stack.pop(), None);
+    assert!(stack.is_empty());
+}
+
+#[test]
+fn test_drops_each_value_once() {
+    // Counts how many times it has been dropped.
+    struct Counted<'a>(&'a AtomicUsize);
+    impl<'a> Drop for Counted<'a> {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let drops = AtomicUsize::new(0);
+    let stack = ConcurrentStack::empty();
+    for _ in 0..(SCAN_THRESHOLD * 3) {
+        stack.push(Counted(&drops));
+    }
+    for _ in 0..(SCAN_THRESHOLD * 2) {
+        drop(stack.pop());
+    }
+    assert_eq!(drops.load(Ordering::Relaxed), SCAN_THRESHOLD * 2);
+    // Popped Nodes are freed once enough of them have been retired.
+    assert!(stack.retired_len.load(Ordering::Relaxed) < SCAN_THRESHOLD);
+    drop(stack);
+    assert_eq!(drops.load(Ordering::Relaxed), SCAN_THRESHOLD * 3);
+}
+
+#[cfg(test)]
+fn retired_contains<T>(stack: &ConcurrentStack<T>, node: *mut Node<T>) -> bool {
+    let mut current = stack.retired.load(Ordering::Acquire);
+    while !current.is_null() {
+        if current == node {
+            return true;
+        }
+        current = unsafe { (*current).retired_next };
+    }
+    false
+}
+
+#[test]
+fn test_aba() {
+    // Replay the classic ABA interleaving step by step. Thread A starts a pop:
+    // it protects the head and reads its next.
+    let stack = ConcurrentStack::empty();
+    stack.push(1);
+    stack.push(2);
+    let hazard = stack.acquire_hazard();
+    let head = stack.head.load(Ordering::Acquire);
+    hazard.protect(head);
+    let next = unsafe { (*head).next };
+
+    // Before A swings the head, thread B pops both elements and then pushes and
+    // pops enough to run several scans. Without hazard pointers the old head
+    // would be freed and its memory reused for a new Node.
+    assert_eq!(stack.pop(), Some(2));
+    assert_eq!(stack.pop(), Some(1));
+    for i in 0..(SCAN_THRESHOLD as u32 * 2) {
+        stack.push(i);
+        assert_ne!(stack.head.load(Ordering::Acquire), head);
+        stack.pop();
+    }
+    stack.push(3);
+    assert!(retired_contains(&stack, head));
+
+    // So when A resumes its compare_exchange fails instead of installing the
+    // freed `next` as the head.
+    assert!(stack
+        .head
+        .compare_exchange(head, next, Ordering::SeqCst, Ordering::Relaxed)
+        .is_err());
+    assert_eq!(stack.pop(), Some(3));
+
+    // Once A is done the next scan frees the old head.
+    drop(hazard);
+    for i in 0..(SCAN_THRESHOLD as u32) {
+        stack.push(i);
+        stack.pop();
+    }
+    assert!(!retired_contains(&stack, head));
+}
+
+#[test]
+fn test_stress() {
+    // Every thread pushes its own values and pops whatever it finds, so the
+    // same few Nodes are popped and pushed over and over. If the stack
+    // suffered from ABA or used freed Nodes values would be lost, duplicated
+    // or corrupted.
+    // Miri is much slower, but a few scans' worth still finds races.
+    const THREADS: u32 = if cfg!(miri) { 4 } else { 8 };
+    const PER_THREAD: u32 = if cfg!(miri) { 500 } else { 50_000 };
+
+    let stack = ConcurrentStack::empty();
+    let popped: Vec<Vec<Box<u32>>> = std::thread::scope(|s| {
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let stack = &stack;
+                s.spawn(move || {
+                    let mut popped = Vec::new();
+                    for i in 0..PER_THREAD {
+                        stack.push(Box::new(t * PER_THREAD + i));
+                        popped.extend(stack.pop());
+                    }
+                    popped
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let mut all: Vec<u32> = popped.into_iter().flatten().map(|n| *n).collect();
+    while let Some(n) = stack.pop() {
+        all.push(*n);
+    }
+    all.sort_unstable();
+    assert_eq!(all, (0..THREADS * PER_THREAD).collect::<Vec<_>>());
+}
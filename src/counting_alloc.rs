@@ -0,0 +1,116 @@
+// Counting allocator for tests.
+//
+// This wraps the system allocator and counts every allocation and deallocation,
+// so tests can check exactly which heap memory an operation uses. The test
+// harness runs tests in parallel on different threads, so the counts are kept
+// per thread and a test only sees its own allocations.
+
+use crate::heapsize::HeapUsage;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Allocator activity on one thread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+    pub allocations: usize,
+    pub deallocations: usize,
+    pub bytes_allocated: usize,
+    pub bytes_deallocated: usize,
+}
+
+impl Counts {
+    const ZERO: Counts = Counts {
+        allocations: 0,
+        deallocations: 0,
+        bytes_allocated: 0,
+        bytes_deallocated: 0,
+    };
+
+    /// Returns the allocations that were made and not yet freed.
+    ///
+    /// Panics if more was freed than allocated.
+    pub fn live(&self) -> HeapUsage {
+        HeapUsage {
+            allocations: self.allocations - self.deallocations,
+            bytes: self.bytes_allocated - self.bytes_deallocated,
+        }
+    }
+}
+
+thread_local! {
+    // Const initialized so that reading it never allocates, which would
+    // recurse into the allocator.
+    static COUNTS: Cell<Counts> = const { Cell::new(Counts::ZERO) };
+}
+
+fn record<F: FnOnce(&mut Counts)>(f: F) {
+    // The thread local is gone while the thread is shutting down, allocations
+    // then are simply not counted.
+    let _ = COUNTS.try_with(|counts| {
+        let mut c = counts.get();
+        f(&mut c);
+        counts.set(c);
+    });
+}
+
+// SAFETY: Every call is forwarded to the system allocator unchanged.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(|c| {
+            c.allocations += 1;
+            c.bytes_allocated += layout.size();
+        });
+        // SAFETY: The caller upholds the contract of `alloc`.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(|c| {
+            c.deallocations += 1;
+            c.bytes_deallocated += layout.size();
+        });
+        // SAFETY: The caller upholds the contract of `dealloc`.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    // The default `realloc` goes through `alloc` and `dealloc`, so growing a
+    // buffer counts as freeing the old one and allocating a new one.
+}
+
+/// Runs `f` and returns its result along with the allocator activity it
+/// caused on this thread.
+pub fn measure<R, F: FnOnce() -> R>(f: F) -> (R, Counts) {
+    let before = COUNTS.with(Cell::get);
+    let result = f();
+    let after = COUNTS.with(Cell::get);
+    let counts = Counts {
+        allocations: after.allocations - before.allocations,
+        deallocations: after.deallocations - before.deallocations,
+        bytes_allocated: after.bytes_allocated - before.bytes_allocated,
+        bytes_deallocated: after.bytes_deallocated - before.bytes_deallocated,
+    };
+    (result, counts)
+}
+
+#[test]
+fn test_counts() {
+    let (b, counts) = measure(|| Box::new(5u64));
+    assert_eq!(
+        counts,
+        Counts {
+            allocations: 1,
+            deallocations: 0,
+            bytes_allocated: 8,
+            bytes_deallocated: 0,
+        }
+    );
+    let ((), counts) = measure(|| drop(b));
+    assert_eq!(counts.deallocations, 1);
+    assert_eq!(counts.bytes_deallocated, 8);
+    assert_eq!(measure(|| 1 + 1).1, Counts::default());
+}
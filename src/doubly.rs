@@ -0,0 +1,823 @@
+// Doubly linked list.
+//
+// The singly linked List from the codelab can only be walked in one direction,
+// so it can't remove from the back or iterate in reverse. Doing that needs each
+// Node to point at both of its neighbours, which means every Node has two
+// "owners". Box can't express that, so the Nodes here are owned by the DList
+// through raw pointers and all of the pointer juggling is kept inside this
+// module behind a safe API.
+//
+// The tests at the bottom of this file exercise all of the unsafe code. When
+// changing it please run them under Miri as well:
+//
+//     cargo +nightly miri test --lib doubly
+//
+// They pass under Miri's default Stacked Borrows checks, and with
+// MIRIFLAGS=-Zmiri-tree-borrows. Under Miri the long list tests use fewer
+// elements, as it is much slower.
+
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+/// Doubly linked list.
+///
+/// Elements can be added and removed at both ends in O(1), and the list can be
+/// iterated from either end.
+pub struct DList<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    // We own Nodes of T, this tells the compiler to treat us that way for drop
+    // checking and variance.
+    marker: PhantomData<Box<Node<T>>>,
+}
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    val: T,
+    prev: Link<T>,
+    next: Link<T>,
+}
+
+impl<T> DList<T> {
+    /// Creates an empty list.
+    pub fn empty() -> Self {
+        DList {
+            head: None,
+            tail: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns true iff the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Returns the number of elements in the list.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Adds a new element to the front of the list.
+    pub fn push_front(&mut self, element: T) {
+        self.insert_between(None, self.head, element);
+    }
+
+    /// Adds a new element to the back of the list.
+    pub fn push_back(&mut self, element: T) {
+        self.insert_between(self.tail, None, element);
+    }
+
+    /// Removes the first element from the list and returns it.
+    pub fn pop_front(&mut self) -> Option<T> {
+        // SAFETY: the head is a Node of this list.
+        self.head.map(|node| unsafe { self.unlink(node) })
+    }
+
+    /// Removes the last element from the list and returns it.
+    pub fn pop_back(&mut self) -> Option<T> {
+        // SAFETY: the tail is a Node of this list.
+        self.tail.map(|node| unsafe { self.unlink(node) })
+    }
+
+    /// Returns the first element of the list.
+    pub fn front(&self) -> Option<&T> {
+        // SAFETY: the head is a Node of this list, which we borrow.
+        self.head.map(|node| unsafe { &(*node.as_ptr()).val })
+    }
+
+    /// Returns the last element of the list.
+    pub fn back(&self) -> Option<&T> {
+        // SAFETY: the tail is a Node of this list, which we borrow.
+        self.tail.map(|node| unsafe { &(*node.as_ptr()).val })
+    }
+
+    /// Returns the first element of the list.
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: the head is a Node of this list, which we borrow mutably.
+        self.head.map(|node| unsafe { &mut (*node.as_ptr()).val })
+    }
+
+    /// Returns the last element of the list.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: the tail is a Node of this list, which we borrow mutably.
+        self.tail.map(|node| unsafe { &mut (*node.as_ptr()).val })
+    }
+
+    /// Returns an iterator over references to the elements.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator that allows modifying each element.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a cursor pointing at the first element of the list.
+    ///
+    /// If the list is empty the cursor points at the "ghost" element, see
+    /// `CursorMut`.
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.head,
+            index: 0,
+            list: self,
+        }
+    }
+
+    /// Returns a cursor pointing at the last element of the list.
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.tail,
+            index: self.len.saturating_sub(1),
+            list: self,
+        }
+    }
+
+    /// Allocates a new Node holding `element` and links it between `prev` and
+    /// `next`.
+    ///
+    /// `prev` and `next` must be neighbouring Nodes of this list, where None
+    /// stands for the ends of the list.
+    fn insert_between(&mut self, prev: Link<T>, next: Link<T>, element: T) {
+        let node = NonNull::from(Box::leak(Box::new(Node {
+            val: element,
+            prev,
+            next,
+        })));
+        // SAFETY: prev and next are Nodes of this list, which we borrow
+        // mutably.
+        unsafe {
+            match prev {
+                Some(prev) => (*prev.as_ptr()).next = Some(node),
+                None => self.head = Some(node),
+            }
+            match next {
+                Some(next) => (*next.as_ptr()).prev = Some(node),
+                None => self.tail = Some(node),
+            }
+        }
+        self.len += 1;
+    }
+
+    /// Links all of the Nodes of `other` between `prev` and `next`, see
+    /// `insert_between`.
+    fn splice_between(&mut self, prev: Link<T>, next: Link<T>, mut other: DList<T>) {
+        let (first, last) = match (other.head.take(), other.tail.take()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return,
+        };
+        // SAFETY: first and last are Nodes that we took from `other`, and prev
+        // and next are Nodes of this list.
+        unsafe {
+            (*first.as_ptr()).prev = prev;
+            (*last.as_ptr()).next = next;
+            match prev {
+                Some(prev) => (*prev.as_ptr()).next = Some(first),
+                None => self.head = Some(first),
+            }
+            match next {
+                Some(next) => (*next.as_ptr()).prev = Some(last),
+                None => self.tail = Some(last),
+            }
+        }
+        self.len += std::mem::replace(&mut other.len, 0);
+    }
+
+    /// Unlinks `node` from the list, frees it and returns its value.
+    ///
+    /// # Safety
+    ///
+    /// `node` must be a Node of this list.
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) -> T {
+        let node = Box::from_raw(node.as_ptr());
+        match node.prev {
+            Some(prev) => (*prev.as_ptr()).next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(next) => (*next.as_ptr()).prev = node.prev,
+            None => self.tail = node.prev,
+        }
+        self.len -= 1;
+        node.val
+    }
+}
+
+impl<T> Drop for DList<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+// The raw pointers stop the compiler from working out that these are safe to
+// send and share, but the list owns its Nodes just like a Box would.
+unsafe impl<T: Send> Send for DList<T> {}
+unsafe impl<T: Sync> Sync for DList<T> {}
+
+impl<T> Default for DList<T> {
+    fn default() -> Self {
+        DList::empty()
+    }
+}
+
+impl<T> std::iter::FromIterator<T> for DList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = DList::empty();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for DList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push_back(element);
+        }
+    }
+}
+
+impl<T: Clone> Clone for DList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for DList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for DList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for DList<T> {}
+
+/// Draining iterator for an owned list.
+pub struct IntoIter<T>(DList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> std::iter::FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for DList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+/// Reference iterator for a reference to a list.
+//
+// The iterator walks in from both ends, `len` counts the elements between head
+// and tail so that we know when the two ends meet.
+pub struct Iter<'a, T: 'a> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        // SAFETY: the list is borrowed for 'a, and len tells us that the head
+        // hasn't passed the tail yet.
+        self.head.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.len -= 1;
+            self.head = node.next;
+            &node.val
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        // SAFETY: see next().
+        self.tail.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.len -= 1;
+            self.tail = node.prev;
+            &node.val
+        })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> std::iter::FusedIterator for Iter<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a DList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Mutable reference iterator for a mutable reference to a list.
+pub struct IterMut<'a, T: 'a> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        // SAFETY: the list is mutably borrowed for 'a, and each Node is only
+        // handed out once since len stops the two ends from crossing.
+        self.head.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.len -= 1;
+            self.head = node.next;
+            &mut node.val
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        // SAFETY: see next().
+        self.tail.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.len -= 1;
+            self.tail = node.prev;
+            &mut node.val
+        })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> std::iter::FusedIterator for IterMut<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a mut DList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A cursor over a DList that can edit the list at its position.
+///
+/// Like the cursors on the std LinkedList there is a "ghost" element between
+/// the back and the front of the list. Moving past either end lands on the
+/// ghost, and moving again wraps around to the other end. All operations are
+/// O(1).
+pub struct CursorMut<'a, T: 'a> {
+    current: Link<T>,
+    index: usize,
+    list: &'a mut DList<T>,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    /// Returns the index of the current element, or None at the ghost.
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Moves to the next element, from the back to the ghost, or from the
+    /// ghost to the front.
+    pub fn move_next(&mut self) {
+        match self.current {
+            // SAFETY: current is a Node of the list we borrow mutably.
+            Some(node) => unsafe {
+                self.current = (*node.as_ptr()).next;
+                self.index += 1;
+            },
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+        }
+    }
+
+    /// Moves to the previous element, from the front to the ghost, or from
+    /// the ghost to the back.
+    pub fn move_prev(&mut self) {
+        match self.current {
+            // SAFETY: current is a Node of the list we borrow mutably.
+            Some(node) => unsafe {
+                self.current = (*node.as_ptr()).prev;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+            },
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.len.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Returns the current element, or None at the ghost.
+    pub fn current(&mut self) -> Option<&mut T> {
+        // SAFETY: current is a Node of the list we borrow mutably, and the
+        // returned reference borrows the cursor.
+        self.current
+            .map(|node| unsafe { &mut (*node.as_ptr()).val })
+    }
+
+    /// Returns the next element, see `move_next`.
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            // SAFETY: current is a Node of the list we borrow mutably.
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        };
+        // SAFETY: see current().
+        next.map(|node| unsafe { &mut (*node.as_ptr()).val })
+    }
+
+    /// Returns the previous element, see `move_prev`.
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            // SAFETY: current is a Node of the list we borrow mutably.
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.list.tail,
+        };
+        // SAFETY: see current().
+        prev.map(|node| unsafe { &mut (*node.as_ptr()).val })
+    }
+
+    /// Returns the Nodes before and after the current one.
+    fn neighbours(&self) -> (Link<T>, Link<T>) {
+        match self.current {
+            // SAFETY: current is a Node of the list we borrow mutably.
+            Some(node) => unsafe { ((*node.as_ptr()).prev, (*node.as_ptr()).next) },
+            None => (self.list.tail, self.list.head),
+        }
+    }
+
+    /// Inserts a new element before the current one.
+    ///
+    /// At the ghost this adds the element to the back of the list.
+    pub fn insert_before(&mut self, element: T) {
+        let (prev, _) = self.neighbours();
+        self.list.insert_between(prev, self.current, element);
+        self.index += 1;
+    }
+
+    /// Inserts a new element after the current one.
+    ///
+    /// At the ghost this adds the element to the front of the list.
+    pub fn insert_after(&mut self, element: T) {
+        let (_, next) = self.neighbours();
+        self.list.insert_between(self.current, next, element);
+        if self.current.is_none() {
+            self.index = self.list.len;
+        }
+    }
+
+    /// Removes the current element and returns it.
+    ///
+    /// The cursor moves on to the next element. At the ghost nothing is
+    /// removed.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        let (_, next) = self.neighbours();
+        self.current = next;
+        // SAFETY: node is a Node of the list we borrow mutably.
+        Some(unsafe { self.list.unlink(node) })
+    }
+
+    /// Removes all of the elements after the current one and returns them as
+    /// a new list.
+    ///
+    /// At the ghost this moves the whole list.
+    pub fn split_after(&mut self) -> DList<T> {
+        let node = match self.current {
+            Some(node) => node,
+            None => {
+                self.index = 0;
+                return std::mem::take(self.list);
+            }
+        };
+        let mut split = DList::empty();
+        // SAFETY: node and the Nodes after it are Nodes of the list we borrow
+        // mutably, we move the ones after it to `split`.
+        unsafe {
+            if let Some(next) = (*node.as_ptr()).next.take() {
+                (*next.as_ptr()).prev = None;
+                split.head = Some(next);
+                split.tail = self.list.tail.replace(node);
+                split.len = self.list.len - self.index - 1;
+                self.list.len = self.index + 1;
+            }
+        }
+        split
+    }
+
+    /// Inserts all of the elements of `other` after the current element.
+    ///
+    /// At the ghost this adds them to the front of the list.
+    pub fn splice_after(&mut self, other: DList<T>) {
+        let (_, next) = self.neighbours();
+        self.list.splice_between(self.current, next, other);
+        if self.current.is_none() {
+            self.index = self.list.len;
+        }
+    }
+}
+
+// Unless the signatures are changed our list must work with non-copyable data,
+// but just to be sure I made this part of the tests :)
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+struct NonCopyable(u32);
+
+#[test]
+fn test_empty() {
+    assert!(DList::<i32>::empty().is_empty());
+    assert_eq!(DList::<i32>::empty().len(), 0);
+}
+
+#[test]
+fn test_push_pop_front() {
+    let mut list = DList::empty();
+    list.push_front(NonCopyable(1));
+    list.push_front(NonCopyable(2));
+    list.push_front(NonCopyable(3));
+    assert!(!list.is_empty());
+    assert_eq!(list.len(), 3);
+
+    assert_eq!(list.pop_front(), Some(NonCopyable(3)));
+    assert_eq!(list.pop_front(), Some(NonCopyable(2)));
+    assert_eq!(list.pop_front(), Some(NonCopyable(1)));
+    assert_eq!(list.pop_front(), None);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn test_push_pop_back() {
+    let mut list = DList::empty();
+    list.push_back(NonCopyable(1));
+    list.push_front(NonCopyable(0));
+    list.push_back(NonCopyable(2));
+    assert_eq!(list.front(), Some(&NonCopyable(0)));
+    assert_eq!(list.back(), Some(&NonCopyable(2)));
+    list.back_mut().unwrap().0 = 3;
+
+    assert_eq!(list.pop_back(), Some(NonCopyable(3)));
+    assert_eq!(list.pop_back(), Some(NonCopyable(1)));
+    list.front_mut().unwrap().0 = 4;
+    assert_eq!(list.pop_back(), Some(NonCopyable(4)));
+    assert_eq!(list.pop_back(), None);
+    assert_eq!(list.front(), None);
+    assert_eq!(list.back(), None);
+}
+
+#[test]
+fn test_into_iter() {
+    let list: DList<_> = (1..=4).map(NonCopyable).collect();
+    let mut iter = list.into_iter();
+    assert_eq!(iter.len(), 4);
+    assert_eq!(iter.next(), Some(NonCopyable(1)));
+    assert_eq!(iter.next_back(), Some(NonCopyable(4)));
+    assert_eq!(iter.next_back(), Some(NonCopyable(3)));
+    assert_eq!(iter.next(), Some(NonCopyable(2)));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn test_iter() {
+    let list: DList<_> = (1..=3).map(NonCopyable).collect();
+    let mut iter = list.iter();
+    assert_eq!(iter.next(), Some(&NonCopyable(1)));
+    assert_eq!(iter.next_back(), Some(&NonCopyable(3)));
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next_back(), Some(&NonCopyable(2)));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    let mut results = Vec::new();
+    for n in &list {
+        results.push(n.0);
+    }
+    for n in list.iter().rev() {
+        results.push(n.0 * 2);
+    }
+    assert_eq!(results, vec![1, 2, 3, 6, 4, 2]);
+}
+
+#[test]
+fn test_iter_mut() {
+    let mut list: DList<_> = (1..=3).map(NonCopyable).collect();
+    let mut iter = list.iter_mut();
+    assert_eq!(iter.next_back(), Some(&mut NonCopyable(3)));
+    iter.next().unwrap().0 = 10;
+    assert_eq!(iter.next(), Some(&mut NonCopyable(2)));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    for n in &mut list {
+        n.0 += 1;
+    }
+    for n in list.iter_mut().rev().take(1) {
+        n.0 *= 2;
+    }
+    let items: Vec<_> = list.into_iter().rev().collect();
+    assert_eq!(items, vec![NonCopyable(8), NonCopyable(3), NonCopyable(11)]);
+}
+
+#[test]
+fn test_traits() {
+    let list: DList<_> = vec!["one".to_string(), "two".to_string()]
+        .into_iter()
+        .collect();
+    let mut copy = list.clone();
+    assert_eq!(list, copy);
+    assert_eq!(format!("{:?}", list), r#"["one", "two"]"#);
+    copy.extend(vec!["three".to_string()]);
+    assert_ne!(list, copy);
+    assert_eq!(DList::<i32>::default(), DList::empty());
+}
+
+#[test]
+fn test_cursor_move() {
+    let mut list: DList<_> = (1..=3).map(NonCopyable).collect();
+    let mut cursor = list.cursor_front_mut();
+    assert_eq!(cursor.index(), Some(0));
+    assert_eq!(cursor.current(), Some(&mut NonCopyable(1)));
+    assert_eq!(cursor.peek_prev(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut NonCopyable(2)));
+
+    cursor.move_prev();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut NonCopyable(1)));
+    assert_eq!(cursor.peek_prev(), Some(&mut NonCopyable(3)));
+
+    cursor.move_prev();
+    assert_eq!(cursor.index(), Some(2));
+    assert_eq!(cursor.current(), Some(&mut NonCopyable(3)));
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+    cursor.move_next();
+    assert_eq!(cursor.index(), Some(0));
+    cursor.move_next();
+    assert_eq!(cursor.index(), Some(1));
+    cursor.current().unwrap().0 = 5;
+
+    let mut cursor = list.cursor_back_mut();
+    assert_eq!(cursor.index(), Some(2));
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some(&mut NonCopyable(5)));
+
+    let mut list = DList::<NonCopyable>::empty();
+    let mut cursor = list.cursor_back_mut();
+    assert_eq!(cursor.index(), None);
+    cursor.move_next();
+    cursor.move_prev();
+    assert_eq!(cursor.current(), None);
+}
+
+#[test]
+fn test_cursor_insert_remove() {
+    let mut list: DList<_> = vec![2, 4].into_iter().collect();
+    let mut cursor = list.cursor_front_mut();
+    cursor.insert_before(1);
+    cursor.insert_after(3);
+    assert_eq!(cursor.index(), Some(1));
+    assert_eq!(cursor.current(), Some(&mut 2));
+
+    cursor.move_next();
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+    cursor.insert_before(5);
+    cursor.insert_after(0);
+    assert_eq!(cursor.index(), None);
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&mut 0));
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 3, 4, 5]);
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(1));
+    assert_eq!(cursor.index(), Some(1));
+    assert_eq!(cursor.current(), Some(&mut 2));
+    cursor.move_prev();
+    assert_eq!(cursor.remove_current(), Some(0));
+    assert_eq!(cursor.index(), Some(0));
+
+    let mut cursor = list.cursor_back_mut();
+    assert_eq!(cursor.remove_current(), Some(5));
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.remove_current(), None);
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.back(), Some(&4));
+    assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), [4, 3, 2]);
+}
+
+#[test]
+fn test_cursor_split_splice() {
+    let mut list: DList<_> = (1..=5).collect();
+    let mut cursor = list.cursor_front_mut();
+    cursor.move_next();
+    let mut tail = cursor.split_after();
+    assert_eq!(tail.len(), 3);
+    assert_eq!(cursor.peek_next(), None);
+    assert_eq!(list.back(), Some(&2));
+    assert_eq!(tail.front(), Some(&3));
+    assert_eq!(tail.iter().rev().copied().collect::<Vec<_>>(), [5, 4, 3]);
+
+    let mut cursor = tail.cursor_front_mut();
+    cursor.splice_after((10..12).collect());
+    assert_eq!(cursor.peek_next(), Some(&mut 10));
+    cursor.move_prev();
+    cursor.splice_after(vec![0].into_iter().collect());
+    assert_eq!(cursor.index(), None);
+    cursor.splice_after(DList::empty());
+    assert_eq!(
+        tail.iter().copied().collect::<Vec<_>>(),
+        [0, 3, 10, 11, 4, 5]
+    );
+
+    let mut cursor = tail.cursor_back_mut();
+    cursor.splice_after(vec![6].into_iter().collect());
+    assert_eq!(tail.back(), Some(&6));
+    assert_eq!(tail.len(), 7);
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.move_prev();
+    let all = cursor.split_after();
+    assert_eq!(cursor.index(), None);
+    assert!(list.is_empty());
+    assert_eq!(all.iter().copied().collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+fn test_drop_long_list() {
+    let len = if cfg!(miri) { 1_000 } else { 1_000_000 };
+    let mut list = DList::empty();
+    for i in 0..len {
+        list.push_back(i);
+    }
+    drop(list);
+}
@@ -0,0 +1,183 @@
+// Fuzzing entry points.
+//
+// The cargo fuzz targets in fuzz/ pass the bytes libFuzzer generates to these
+// functions, which panic if any invariant is broken. They live in the library
+// rather than in the targets so that the tests below can replay the inputs in
+// fuzz/corpus, and any crashes cargo fuzz saved to fuzz/artifacts, as ordinary
+// unit tests without a nightly compiler or cargo fuzz. To keep a crashing input
+// as a regression test once it is fixed, move it into the corpus.
+//
+// Run the fuzzers with `cargo +nightly fuzz run list_ops` or `parse`.
+
+use crate::sequence::Sequence;
+use crate::{linkedlist, solution};
+use std::collections::VecDeque;
+
+/// Characters for the elements, chosen to exercise quoting in `Display`.
+const ALPHABET: [char; 8] = ['a', 'b', '1', ' ', ',', '"', '\\', 'é'];
+
+/// Reads the input a byte at a time.
+struct Input<'a> {
+    bytes: std::slice::Iter<'a, u8>,
+}
+
+impl<'a> Input<'a> {
+    fn is_empty(&self) -> bool {
+        self.bytes.len() == 0
+    }
+
+    /// Returns the next byte, or 0 once the input runs out.
+    fn byte(&mut self) -> u8 {
+        self.bytes.next().copied().unwrap_or(0)
+    }
+
+    /// Returns a short string of ALPHABET characters.
+    fn element(&mut self) -> String {
+        let len = self.byte() % 4;
+        (0..len)
+            .map(|_| ALPHABET[self.byte() as usize % ALPHABET.len()])
+            .collect()
+    }
+}
+
+/// Decodes `data` into operations, runs them against a solution::List and a
+/// linkedlist::List, and checks them against a VecDeque after every step.
+///
+/// Each operation is one byte, followed by an element for pushes.
+pub fn list_ops(data: &[u8]) {
+    let mut input = Input { bytes: data.iter() };
+    let mut list = solution::List::empty();
+    let mut list_model = VecDeque::new();
+    let mut linked = linkedlist::List::new();
+    let mut linked_model = VecDeque::new();
+
+    while !input.is_empty() {
+        match input.byte() % 5 {
+            0 => {
+                let element = input.element();
+                list.push(element.clone());
+                list_model.push_front(element.clone());
+                Sequence::push(&mut linked, element.clone());
+                linked_model.push_front(element);
+            }
+            1 => {
+                let element = input.element();
+                list.push_back(element.clone());
+                list_model.push_back(element);
+            }
+            2 => {
+                assert_eq!(list.pop(), list_model.pop_front());
+                assert_eq!(Sequence::pop(&mut linked), linked_model.pop_front());
+            }
+            3 => {
+                assert_eq!(list.is_empty(), (&list).into_iter().next().is_none());
+                assert_eq!(list.len(), (&list).into_iter().count());
+                assert!((&list).into_iter().eq(&list_model));
+                assert_eq!(linked.is_empty(), linked.iter().next().is_none());
+                assert!(linked.iter().eq(&linked_model));
+            }
+            _ => {
+                let text = linked.to_string();
+                let parsed: linkedlist::List<String> = text
+                    .parse()
+                    .unwrap_or_else(|e| panic!("failed to parse {:?}: {}", text, e));
+                assert!(parsed.iter().eq(&linked_model), "{:?}", text);
+            }
+        }
+        assert_eq!(list.len(), list_model.len());
+        assert_eq!(list.is_empty(), list_model.is_empty());
+        assert_eq!(linked.is_empty(), linked_model.is_empty());
+    }
+
+    // Popping everything returns the elements in the reverse of the order they
+    // were pushed.
+    while let Some(element) = list_model.pop_front() {
+        assert_eq!(list.pop(), Some(element));
+    }
+    assert_eq!(list.pop(), None);
+    while let Some(element) = linked_model.pop_front() {
+        assert_eq!(Sequence::pop(&mut linked), Some(element));
+    }
+    assert!(linked.is_empty());
+}
+
+/// Parses `data` as a linkedlist::List, and checks that anything that parses
+/// formats back to text that parses to the same list.
+pub fn parse(data: &[u8]) {
+    let text = match std::str::from_utf8(data) {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+    check_parse::<String>(text);
+    check_parse::<i32>(text);
+}
+
+fn check_parse<T>(text: &str)
+where
+    T: std::str::FromStr + std::fmt::Display + PartialEq + std::fmt::Debug,
+{
+    match text.parse::<linkedlist::List<T>>() {
+        Ok(list) => {
+            let formatted = list.to_string();
+            let reparsed: linkedlist::List<T> = match formatted.parse() {
+                Ok(reparsed) => reparsed,
+                Err(_) => panic!(
+                    "{:?} formatted as {:?} which fails to parse",
+                    text, formatted
+                ),
+            };
+            assert!(list.iter().eq(reparsed.iter()), "{:?}", formatted);
+        }
+        Err(e) => {
+            assert!(e.offset() <= text.len(), "{:?}", text);
+            assert!(text.is_char_boundary(e.offset()), "{:?}", text);
+        }
+    }
+}
+
+/// Runs `target` on every input saved for it, and panics naming the first one
+/// that fails.
+#[cfg(test)]
+fn replay(name: &str, target: fn(&[u8])) {
+    let fuzz = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz");
+    let mut inputs = 0;
+    for dir in ["corpus", "artifacts"] {
+        // There are only artifacts after cargo fuzz finds a crash.
+        let entries = match std::fs::read_dir(fuzz.join(dir).join(name)) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries {
+            let path = entry.unwrap().path();
+            let data = std::fs::read(&path).unwrap();
+            if std::panic::catch_unwind(|| target(&data)).is_err() {
+                panic!("{} failed", path.display());
+            }
+            inputs += 1;
+        }
+    }
+    assert!(inputs > 0, "no inputs for {}", name);
+}
+
+#[test]
+fn test_replay_list_ops() {
+    replay("list_ops", list_ops);
+}
+
+#[test]
+fn test_replay_parse() {
+    replay("parse", parse);
+}
+
+#[test]
+fn test_list_ops_random() {
+    // A quick run over random inputs, so that the invariants are checked even
+    // without the fuzzer.
+    let mut rng = crate::rng::Rng::new(20);
+    for _ in 0..500 {
+        let len = rng.below(200) as usize;
+        let data: Vec<u8> = (0..len).map(|_| rng.next_u64() as u8).collect();
+        list_ops(&data);
+        parse(&data);
+    }
+}
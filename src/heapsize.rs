@@ -0,0 +1,148 @@
+// Heap memory accounting.
+//
+// `std::mem::size_of` only tells us how big a value is inline. A List is only a
+// few words inline but owns one heap allocation per element, and each element
+// may own allocations of its own, such as the buffer of a String. The HeapSize
+// trait reports those allocations so that we can compare how much memory the
+// different list designs use.
+//
+// Sizes are the sizes that were requested from the allocator, the allocator
+// itself may round them up.
+
+/// The heap allocations owned by a value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeapUsage {
+    /// The number of live allocations.
+    pub allocations: usize,
+    /// The total size of those allocations in bytes.
+    pub bytes: usize,
+}
+
+impl HeapUsage {
+    /// No allocations.
+    pub const NONE: HeapUsage = HeapUsage {
+        allocations: 0,
+        bytes: 0,
+    };
+
+    /// A single allocation of `bytes`, or none if `bytes` is 0 since the
+    /// standard containers don't allocate for zero-sized buffers.
+    pub fn allocation(bytes: usize) -> Self {
+        HeapUsage {
+            allocations: (bytes > 0) as usize,
+            bytes,
+        }
+    }
+}
+
+impl std::ops::Add for HeapUsage {
+    type Output = HeapUsage;
+
+    fn add(self, other: HeapUsage) -> HeapUsage {
+        HeapUsage {
+            allocations: self.allocations + other.allocations,
+            bytes: self.bytes + other.bytes,
+        }
+    }
+}
+
+impl std::ops::AddAssign for HeapUsage {
+    fn add_assign(&mut self, other: HeapUsage) {
+        *self = *self + other;
+    }
+}
+
+impl std::iter::Sum for HeapUsage {
+    fn sum<I: Iterator<Item = HeapUsage>>(iter: I) -> Self {
+        iter.fold(HeapUsage::NONE, |a, b| a + b)
+    }
+}
+
+/// Types that can report the heap memory they own.
+pub trait HeapSize {
+    /// Returns the allocations owned by this value, not counting the value
+    /// itself.
+    fn heap_size(&self) -> HeapUsage;
+}
+
+macro_rules! no_heap {
+    ($($t:ty),*) => {
+        $(
+            impl HeapSize for $t {
+                fn heap_size(&self) -> HeapUsage {
+                    HeapUsage::NONE
+                }
+            }
+        )*
+    };
+}
+
+no_heap!(u8, u16, u32, u64, u128, usize);
+no_heap!(i8, i16, i32, i64, i128, isize);
+no_heap!(f32, f64, bool, char, ());
+
+impl HeapSize for String {
+    fn heap_size(&self) -> HeapUsage {
+        HeapUsage::allocation(self.capacity())
+    }
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+    fn heap_size(&self) -> HeapUsage {
+        let buffer = HeapUsage::allocation(self.capacity() * std::mem::size_of::<T>());
+        buffer + self.iter().map(T::heap_size).sum()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Box<T> {
+    fn heap_size(&self) -> HeapUsage {
+        HeapUsage::allocation(std::mem::size_of::<T>()) + (**self).heap_size()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Option<T> {
+    fn heap_size(&self) -> HeapUsage {
+        self.as_ref().map_or(HeapUsage::NONE, T::heap_size)
+    }
+}
+
+#[test]
+fn test_std_types() {
+    assert_eq!(5u32.heap_size(), HeapUsage::NONE);
+    assert_eq!(String::new().heap_size(), HeapUsage::NONE);
+    assert_eq!(
+        String::with_capacity(10).heap_size(),
+        HeapUsage {
+            allocations: 1,
+            bytes: 10
+        }
+    );
+
+    let mut strings = Vec::with_capacity(4);
+    strings.push("abc".to_string());
+    strings.push(String::new());
+    assert_eq!(
+        strings.heap_size(),
+        HeapUsage {
+            allocations: 2,
+            bytes: 4 * std::mem::size_of::<String>() + 3
+        }
+    );
+
+    assert_eq!(Box::new(()).heap_size(), HeapUsage::NONE);
+    assert_eq!(Box::new(7u64).heap_size(), HeapUsage::allocation(8));
+    assert_eq!(Some(Box::new(7u64)).heap_size(), HeapUsage::allocation(8));
+    assert_eq!(None::<Box<u64>>.heap_size(), HeapUsage::NONE);
+}
+
+#[test]
+fn test_matches_allocator() {
+    use crate::counting_alloc::measure;
+
+    let (strings, counts) = measure(|| {
+        (0..100)
+            .map(|i| Box::new(i.to_string()))
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(strings.heap_size(), counts.live());
+}
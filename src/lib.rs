@@ -1,5 +1,22 @@
 pub mod codelab;
 pub mod solution;
 
+pub mod arena;
+pub mod concurrent;
+#[cfg(test)]
+mod counting_alloc;
+pub mod doubly;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod fuzz;
+pub mod heapsize;
 pub mod linkedlist;
-mod slides;
\ No newline at end of file
+pub mod loader;
+#[cfg(test)]
+mod model;
+pub mod ratings;
+mod rng;
+pub mod sequence;
+pub mod skiplist;
+mod slides;
+pub mod standings;
+pub mod stream;
\ No newline at end of file
@@ -1,4 +1,7 @@
-#[derive(Clone)]
+use crate::heapsize::{HeapSize, HeapUsage};
+use std::rc::Rc;
+use std::sync::Arc;
+
 pub enum List<T> {
     Empty,
     Cons(T, Box<List<T>>),
@@ -25,9 +28,17 @@ impl<T> List<T> {
     }
 
     pub fn pop(self) -> Option<(Self, T)> {
-        match self {
+        // List implements Drop so we can't move the element and tail out of it
+        // with a pattern. Instead we read them out of a list that will never be
+        // dropped.
+        let list = std::mem::ManuallyDrop::new(self);
+        match &*list {
             List::Empty => None,
-            List::Cons(element, tail) => Some((*tail, element)),
+            // SAFETY: `list` is never dropped, so each field is only read once
+            // and ownership of it moves to the caller.
+            List::Cons(element, tail) => unsafe {
+                Some((*std::ptr::read(tail), std::ptr::read(element)))
+            },
         }
     }
 
@@ -46,6 +57,88 @@ impl<T> List<T> {
     }
 }
 
+// The derived implementations of Drop and Clone recurse once per element, which
+// overflows the stack for long lists, so we walk the list in a loop instead.
+
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        // Unlink each tail before the cell holding it is dropped so that
+        // dropping a cell never recurses.
+        if let List::Cons(_, tail) = self {
+            let mut current = std::mem::replace(&mut **tail, List::Empty);
+            while let List::Cons(_, tail) = &mut current {
+                current = std::mem::replace(&mut **tail, List::Empty);
+            }
+        }
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+/// Serializes a List as a sequence, head first.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for List<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        let mut len = 0;
+        let mut current = self;
+        while let List::Cons(_, tail) = current {
+            len += 1;
+            current = tail;
+        }
+
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        let mut current = self;
+        while let List::Cons(element, tail) = current {
+            seq.serialize_element(element)?;
+            current = tail;
+        }
+        seq.end()
+    }
+}
+
+/// Deserializes a List from a sequence.
+///
+/// Like `collect` this builds the list front to back in a loop, so long
+/// sequences don't use any stack.
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for List<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ListVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: serde::Deserialize<'de>> serde::de::Visitor<'de> for ListVisitor<T> {
+            type Value = List<T>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a sequence")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<List<T>, A::Error> {
+                let mut result = List::Empty;
+                let mut end = &mut result;
+                while let Some(element) = seq.next_element()? {
+                    *end = List::Cons(element, Box::new(List::Empty));
+                    end = match end {
+                        List::Cons(_, end_tail) => end_tail,
+                        List::Empty => unreachable!(),
+                    };
+                }
+                Ok(result)
+            }
+        }
+
+        deserializer.deserialize_seq(ListVisitor(std::marker::PhantomData))
+    }
+}
+
 #[test]
 fn test_empty() {
     assert!(List::<i32>::new().is_empty());
@@ -85,6 +178,115 @@ fn example_clone() {
     assert!(list.is_empty());
 }
 
+#[test]
+fn test_clone() {
+    let list = List::new().push(1).push(2).push(3);
+    let copy = list.clone();
+    assert_eq!(format!("{}", copy), "3, 2, 1");
+    assert_eq!(format!("{}", list), "3, 2, 1");
+}
+
+#[test]
+fn test_drop_long_list() {
+    let mut list = List::new();
+    for i in 0..10_000_000 {
+        list = list.push(i);
+    }
+    drop(list);
+}
+
+#[test]
+fn test_clone_long_list() {
+    let mut list = List::new();
+    for i in 0..10_000_000 {
+        list = list.push(i);
+    }
+    let copy = list.clone();
+    assert_eq!(copy.head(), Some(&9_999_999));
+    drop(list);
+    drop(copy);
+}
+
+#[cfg(all(test, feature = "serde"))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct NonCopyable(u32);
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_serde() {
+    use serde_test::{assert_ser_tokens, Token};
+
+    let list = List::new().push(NonCopyable(2)).push(NonCopyable(1));
+    let json = serde_json::to_string(&list).unwrap();
+    assert_eq!(json, "[1,2]");
+    let back: List<NonCopyable> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.head(), Some(&NonCopyable(1)));
+    assert_eq!(back.tail().head(), Some(&NonCopyable(2)));
+    assert!(back.tail().tail().is_empty());
+
+    assert_ser_tokens(
+        &List::new().push(1u8),
+        &[Token::Seq { len: Some(1) }, Token::U8(1), Token::SeqEnd],
+    );
+    assert!(serde_json::from_str::<List<u32>>("[1, \"two\"]").is_err());
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_serde_long_list() {
+    let mut json = String::from("[0");
+    for i in 1..1_000_000 {
+        json.push_str(&format!(",{}", i));
+    }
+    json.push(']');
+    let list: List<u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(list.head(), Some(&0));
+    assert_eq!(serde_json::to_string(&list).unwrap(), json);
+}
+
+/// Counts one allocation per element, for the Box holding its tail, plus
+/// whatever the elements own.
+impl<T: HeapSize> HeapSize for List<T> {
+    fn heap_size(&self) -> HeapUsage {
+        let cells = HeapUsage::allocation(std::mem::size_of::<List<T>>());
+        self.iter().map(|element| cells + element.heap_size()).sum()
+    }
+}
+
+#[test]
+fn test_heap_size() {
+    use crate::counting_alloc::{measure, Counts};
+
+    let cell = std::mem::size_of::<List<String>>();
+    let (list, counts) = measure(|| List::new().push("a".to_string()));
+    assert_eq!(
+        counts,
+        Counts {
+            allocations: 2,
+            bytes_allocated: cell + 1,
+            ..Counts::default()
+        }
+    );
+    assert_eq!(list.heap_size(), counts.live());
+
+    let (list, counts) = measure(|| list.push(String::new()));
+    assert_eq!(counts.allocations, 1);
+    assert_eq!(counts.bytes_allocated, cell);
+    assert_eq!(list.heap_size().allocations, 3);
+
+    let (popped, counts) = measure(|| list.pop());
+    let (_, element) = popped.unwrap();
+    assert_eq!(element, "");
+    assert_eq!(counts.deallocations, 1);
+    assert_eq!(counts.bytes_deallocated, cell);
+
+    let (list, counts) = measure(|| (0..100).map(|i| i.to_string()).collect::<List<_>>());
+    assert_eq!(list.heap_size(), counts.live());
+    let ((), counts) = measure(|| drop(list));
+    assert_eq!(counts.allocations, 0);
+    assert_eq!(counts.deallocations, 200);
+}
+
 #[test]
 fn example_format() {
     println!("My new list {}!", List::new().push("foobar"))
@@ -102,12 +304,35 @@ impl<T: std::fmt::Display> std::fmt::Display for List<T> {
             } else {
                 first = false;
             }
-            write!(f, "{}", element)?;
+            write_element(f, element)?;
         }
         return Ok(());
     }
 }
 
+/// Writes one element of a List in the format that `from_str` parses.
+///
+/// Elements are written as is, unless they are empty or contain a `,`, `"` or
+/// `\`. Those are wrapped in quotes and any `"` or `\` inside is escaped with a
+/// `\`, so that they can't be confused with the separator or the empty list.
+fn write_element<T: std::fmt::Display>(
+    f: &mut std::fmt::Formatter,
+    element: &T,
+) -> std::fmt::Result {
+    let text = element.to_string();
+    if !text.is_empty() && !text.contains([',', '"', '\\']) {
+        return f.write_str(&text);
+    }
+    f.write_str("\"")?;
+    for c in text.chars() {
+        if c == '"' || c == '\\' {
+            f.write_str("\\")?;
+        }
+        write!(f, "{}", c)?;
+    }
+    f.write_str("\"")
+}
+
 #[test]
 fn test_fmt() {
     let list = List::new();
@@ -123,4 +348,764 @@ fn test_fmt() {
     assert_eq!(format!("{}", list), "one");
     let list = list.push("two").push("three");
     assert_eq!(format!("{}", list), "three, two, one");
+}
+
+/// An error from parsing a List.
+#[derive(Debug, PartialEq)]
+pub struct ParseListError<E> {
+    offset: usize,
+    kind: ParseListErrorKind<E>,
+}
+
+/// The reason parsing a List failed.
+#[derive(Debug, PartialEq)]
+pub enum ParseListErrorKind<E> {
+    /// An element failed to parse.
+    Element(E),
+    /// A quoted element has no closing quote.
+    UnterminatedQuote,
+    /// A `\` in a quoted element is followed by something other than `"` or
+    /// `\`.
+    InvalidEscape,
+    /// Something other than `, ` or the end of the input follows an element.
+    ExpectedSeparator,
+}
+
+impl<E> ParseListError<E> {
+    /// Returns the byte offset into the input where the error was found.
+    ///
+    /// For element errors this is the start of the element.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns what went wrong.
+    pub fn kind(&self) -> &ParseListErrorKind<E> {
+        &self.kind
+    }
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for ParseListError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.kind {
+            ParseListErrorKind::Element(_) => write!(f, "invalid element")?,
+            ParseListErrorKind::UnterminatedQuote => write!(f, "unterminated quote")?,
+            ParseListErrorKind::InvalidEscape => write!(f, "invalid escape")?,
+            ParseListErrorKind::ExpectedSeparator => write!(f, "expected \", \"")?,
+        }
+        write!(f, " at byte {}", self.offset)?;
+        if let ParseListErrorKind::Element(e) = &self.kind {
+            write!(f, ": {}", e)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for ParseListError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ParseListErrorKind::Element(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the format written by `Display`, such as `3, 2, 1`.
+///
+/// Elements are separated by `, `. An element can be wrapped in quotes, in
+/// which case it can contain anything, with `"` and `\` escaped as `\"` and
+/// `\\`. The empty string is the empty list.
+impl<T: std::str::FromStr> std::str::FromStr for List<T> {
+    type Err = ParseListError<T::Err>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |offset, kind| ParseListError { offset, kind };
+
+        if s.is_empty() {
+            return Ok(List::new());
+        }
+        // Each pass parses one element, then either reaches the end of the
+        // input and stops or skips the ", " before the next element.
+        let mut elements = Vec::new();
+        let mut offset = 0;
+        loop {
+            let start = offset;
+            let rest = &s[offset..];
+            let text = if let Some(quoted) = rest.strip_prefix('"') {
+                let mut text = String::new();
+                let mut chars = quoted.char_indices();
+                loop {
+                    match chars.next() {
+                        Some((i, '"')) => {
+                            offset += 1 + i + 1;
+                            break;
+                        }
+                        Some((i, '\\')) => match chars.next() {
+                            Some((_, c)) if c == '"' || c == '\\' => text.push(c),
+                            _ => {
+                                return Err(error(
+                                    offset + 1 + i,
+                                    ParseListErrorKind::InvalidEscape,
+                                ))
+                            }
+                        },
+                        Some((_, c)) => text.push(c),
+                        None => return Err(error(start, ParseListErrorKind::UnterminatedQuote)),
+                    }
+                }
+                std::borrow::Cow::Owned(text)
+            } else {
+                let len = rest.find(',').unwrap_or(rest.len());
+                offset += len;
+                std::borrow::Cow::Borrowed(&rest[..len])
+            };
+            let element = text
+                .parse()
+                .map_err(|e| error(start, ParseListErrorKind::Element(e)))?;
+            elements.push(element);
+
+            if offset == s.len() {
+                break;
+            }
+            if !s[offset..].starts_with(", ") {
+                return Err(error(offset, ParseListErrorKind::ExpectedSeparator));
+            }
+            offset += 2;
+        }
+
+        Ok(elements.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+fn to_vec<T: Clone>(list: &List<T>) -> Vec<T> {
+    list.iter().cloned().collect()
+}
+
+#[test]
+fn test_fmt_quoting() {
+    let list = List::new()
+        .push("")
+        .push(r#"back\slash"#)
+        .push(r#"say "hi""#)
+        .push("one, two")
+        .push(" spaced ");
+    assert_eq!(
+        format!("{}", list),
+        r#" spaced , "one, two", "say \"hi\"", "back\\slash", """#
+    );
+    assert_eq!(format!("{}", List::new().push("")), r#""""#);
+}
+
+#[test]
+fn test_parse() {
+    let list: List<i32> = "3, 2, 1".parse().unwrap();
+    assert_eq!(to_vec(&list), vec![3, 2, 1]);
+    let list: List<i32> = "".parse().unwrap();
+    assert!(list.is_empty());
+    let list: List<i32> = "\"-7\"".parse().unwrap();
+    assert_eq!(to_vec(&list), vec![-7]);
+
+    let list: List<String> = r#" spaced , "one, two", "say \"hi\"", "back\\slash", """#
+        .parse()
+        .unwrap();
+    assert_eq!(
+        to_vec(&list),
+        vec![" spaced ", "one, two", r#"say "hi""#, r#"back\slash"#, ""]
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    let error = "1, x, 3".parse::<List<u8>>().err().unwrap();
+    assert_eq!(error.offset(), 3);
+    assert_eq!(
+        error.kind(),
+        &ParseListErrorKind::Element("x".parse::<u8>().unwrap_err())
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid element at byte 3: invalid digit found in string"
+    );
+    assert!(std::error::Error::source(&error).is_some());
+
+    let error = |s: &str| {
+        let error = s.parse::<List<String>>().err().unwrap();
+        (error.offset(), error.kind)
+    };
+    use ParseListErrorKind::*;
+    assert_eq!(error(r#"a, "bc"#), (3, UnterminatedQuote));
+    assert_eq!(error(r#"a, "b\c""#), (5, InvalidEscape));
+    assert_eq!(error(r#""a\"#), (2, InvalidEscape));
+    assert_eq!(error(r#""a"b"#), (3, ExpectedSeparator));
+    assert_eq!(error("a,b"), (1, ExpectedSeparator));
+    assert_eq!(error("a,"), (1, ExpectedSeparator));
+    assert_eq!(
+        "1, ".parse::<List<u8>>().err().unwrap().offset(),
+        3,
+        "a trailing separator is an empty element"
+    );
+}
+
+#[test]
+fn test_parse_format_roundtrip() {
+    let mut rng = crate::rng::Rng::new(0x5eed);
+    let pieces = [
+        "a", "b", "7", "", " ", ",", ", ", "\"", "\\", "\\\"", "é", "🦀",
+    ];
+    for _ in 0..2000 {
+        let mut list = List::new();
+        for _ in 0..rng.below(6) {
+            let mut element = String::new();
+            for _ in 0..rng.below(5) {
+                let piece = rng.choose(&pieces);
+                element.push_str(piece);
+            }
+            list = list.push(element);
+        }
+        let text = list.to_string();
+        let parsed: List<String> = text.parse().unwrap_or_else(|e| panic!("{:?}: {}", text, e));
+        assert_eq!(to_vec(&parsed), to_vec(&list), "{:?}", text);
+    }
+
+    for _ in 0..2000 {
+        let mut list = List::new();
+        for _ in 0..rng.below(6) {
+            list = list.push(rng.next_u64() as i64);
+        }
+        let parsed: List<i64> = list.to_string().parse().unwrap();
+        assert_eq!(to_vec(&parsed), to_vec(&list));
+    }
+}
+
+// Functional combinators.
+//
+// These are the building blocks that functional code would normally write with
+// recursion, but a recursive implementation uses a stack frame per element and
+// overflows on long lists. Instead each one is written as a loop, usually on
+// top of the iterators below.
+
+/// Iterator over references to the elements of a List.
+pub struct Iter<'a, T: 'a> {
+    current: &'a List<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.current {
+            List::Empty => None,
+            List::Cons(element, tail) => {
+                self.current = tail;
+                Some(element)
+            }
+        }
+    }
+}
+
+impl<'a, T> std::iter::FusedIterator for Iter<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator that pops each element of an owned List.
+pub struct IntoIter<T>(List<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (tail, element) = std::mem::replace(&mut self.0, List::Empty).pop()?;
+        self.0 = tail;
+        Some(element)
+    }
+}
+
+impl<T> std::iter::FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+/// Collects an Iterator into a List, keeping its order.
+///
+/// The first element yielded becomes the head of the list.
+impl<T> std::iter::FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        // Build the list front to back, filling in the Empty at the end each
+        // time.
+        let mut result = List::Empty;
+        let mut end = &mut result;
+        for element in iter {
+            *end = List::Cons(element, Box::new(List::Empty));
+            end = match end {
+                List::Cons(_, end_tail) => end_tail,
+                List::Empty => unreachable!(),
+            };
+        }
+        result
+    }
+}
+
+impl<T> List<T> {
+    /// Returns an iterator over references to the elements, head first.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { current: self }
+    }
+
+    /// Returns the head and tail, or None for the empty list.
+    ///
+    /// This is handy for matching on the first few elements of a list, such
+    /// as `Some((1, rest))`.
+    pub fn uncons(&self) -> Option<(&T, &Self)> {
+        match self {
+            List::Empty => None,
+            List::Cons(element, tail) => Some((element, tail)),
+        }
+    }
+
+    /// Returns a list of the results of calling `f` on each element.
+    pub fn map<U, F: FnMut(T) -> U>(self, f: F) -> List<U> {
+        self.into_iter().map(f).collect()
+    }
+
+    /// Returns a list of the elements for which `f` returns true.
+    pub fn filter<F: FnMut(&T) -> bool>(self, f: F) -> Self {
+        self.into_iter().filter(f).collect()
+    }
+
+    /// Combines the elements with `f`, starting from `init` and the head.
+    ///
+    /// `[1, 2, 3].fold_left(init, f)` is `f(f(f(init, 1), 2), 3)`.
+    pub fn fold_left<B, F: FnMut(B, T) -> B>(self, init: B, f: F) -> B {
+        self.into_iter().fold(init, f)
+    }
+
+    /// Combines the elements with `f`, starting from `init` and the last
+    /// element.
+    ///
+    /// `[1, 2, 3].fold_right(init, f)` is `f(1, f(2, f(3, init)))`.
+    pub fn fold_right<B, F: FnMut(T, B) -> B>(self, init: B, mut f: F) -> B {
+        // Folding over the reversed list visits the elements in the same order
+        // as the usual recursive definition, without the recursion.
+        self.reverse()
+            .fold_left(init, |acc, element| f(element, acc))
+    }
+
+    /// Pairs up the elements of two lists.
+    ///
+    /// The result is as long as the shorter list, the rest of the longer list
+    /// is dropped.
+    pub fn zip<U>(self, other: List<U>) -> List<(T, U)> {
+        self.into_iter().zip(other).collect()
+    }
+
+    /// Returns this list followed by `other`.
+    ///
+    /// This takes time proportional to the length of this list, `other` is
+    /// reused as is.
+    pub fn concat(mut self, other: Self) -> Self {
+        let mut end = &mut self;
+        while let List::Cons(_, tail) = end {
+            end = tail;
+        }
+        *end = other;
+        self
+    }
+
+    /// Returns the list in reverse order.
+    pub fn reverse(self) -> Self {
+        self.into_iter().fold(List::new(), List::push)
+    }
+
+    /// Returns the first `n` elements.
+    pub fn take(self, n: usize) -> Self {
+        self.split_at(n).0
+    }
+
+    /// Returns the list without its first `n` elements.
+    pub fn drop(self, n: usize) -> Self {
+        self.split_at(n).1
+    }
+
+    /// Splits the list into its first `n` elements and the rest.
+    ///
+    /// If the list is shorter than `n` the rest is empty.
+    pub fn split_at(mut self, n: usize) -> (Self, Self) {
+        let mut end = &mut self;
+        for _ in 0..n {
+            match end {
+                List::Cons(_, tail) => end = tail,
+                List::Empty => break,
+            }
+        }
+        let rest = std::mem::replace(end, List::Empty);
+        (self, rest)
+    }
+
+    /// Returns the element at index `n`, counting from the head.
+    pub fn nth(&self, n: usize) -> Option<&T> {
+        self.iter().nth(n)
+    }
+
+    /// Returns the last element.
+    pub fn last(&self) -> Option<&T> {
+        self.iter().last()
+    }
+
+    /// Returns the first element for which `f` returns true.
+    pub fn find<F: FnMut(&T) -> bool>(&self, mut f: F) -> Option<&T> {
+        self.iter().find(|element| f(element))
+    }
+}
+
+#[cfg(test)]
+fn numbers(range: std::ops::Range<i32>) -> List<i32> {
+    range.collect()
+}
+
+#[test]
+fn test_iterators() {
+    let list = numbers(1..4);
+    let mut iter = list.iter();
+    assert_eq!(iter.next(), Some(&1));
+    assert_eq!(iter.next(), Some(&2));
+    assert_eq!(iter.next(), Some(&3));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next(), None);
+
+    let mut sum = 0;
+    for n in &list {
+        sum += n;
+    }
+    assert_eq!(sum, 6);
+
+    let items: Vec<_> = list.into_iter().collect();
+    assert_eq!(items, vec![1, 2, 3]);
+    assert_eq!(List::<i32>::new().into_iter().next(), None);
+}
+
+#[test]
+fn test_from_iter() {
+    let list: List<_> = vec!["a", "b", "c"].into_iter().collect();
+    assert_eq!(format!("{}", list), "a, b, c");
+    let list: List<i32> = std::iter::empty().collect();
+    assert!(list.is_empty());
+}
+
+#[test]
+fn test_uncons() {
+    let list = numbers(1..3);
+    let (head, rest) = list.uncons().unwrap();
+    assert_eq!(*head, 1);
+    assert!(matches!(rest.uncons(), Some((2, rest)) if rest.is_empty()));
+    assert!(List::<i32>::new().uncons().is_none());
+}
+
+#[test]
+fn test_map_filter() {
+    let list = numbers(1..6).map(|n| n * 10);
+    assert_eq!(to_vec(&list), vec![10, 20, 30, 40, 50]);
+    let list = list.filter(|n| n % 20 != 0);
+    assert_eq!(to_vec(&list), vec![10, 30, 50]);
+    let list = list.map(|n| n.to_string());
+    assert_eq!(format!("{}", list), "10, 30, 50");
+}
+
+#[test]
+fn test_folds() {
+    let sub_left = numbers(1..4).fold_left(10, |acc, n| acc - n);
+    assert_eq!(sub_left, ((10 - 1) - 2) - 3);
+    let sub_right = numbers(1..4).fold_right(10, |n, acc| n - acc);
+    assert_eq!(sub_right, 1 - (2 - (3 - 10)));
+
+    // fold_right with push rebuilds the same list.
+    let copy = numbers(1..4).fold_right(List::new(), |n, acc| acc.push(n));
+    assert_eq!(to_vec(&copy), vec![1, 2, 3]);
+    assert_eq!(List::<i32>::new().fold_right(7, |n, acc| n + acc), 7);
+}
+
+#[test]
+fn test_zip_concat_reverse() {
+    let zipped = numbers(1..4).zip(vec!["a", "b"].into_iter().collect());
+    assert_eq!(to_vec(&zipped), vec![(1, "a"), (2, "b")]);
+
+    let list = numbers(1..3).concat(numbers(3..5));
+    assert_eq!(to_vec(&list), vec![1, 2, 3, 4]);
+    let list = List::new().concat(list).concat(List::new());
+    assert_eq!(to_vec(&list), vec![1, 2, 3, 4]);
+
+    assert_eq!(to_vec(&list.reverse()), vec![4, 3, 2, 1]);
+    assert!(List::<i32>::new().reverse().is_empty());
+}
+
+#[test]
+fn test_take_drop_split_at() {
+    assert_eq!(to_vec(&numbers(0..5).take(2)), vec![0, 1]);
+    assert_eq!(to_vec(&numbers(0..5).take(9)), vec![0, 1, 2, 3, 4]);
+    assert_eq!(to_vec(&numbers(0..5).drop(2)), vec![2, 3, 4]);
+    assert!(numbers(0..5).drop(5).is_empty());
+    assert!(numbers(0..5).drop(9).is_empty());
+
+    let (front, back) = numbers(0..5).split_at(0);
+    assert!(front.is_empty());
+    assert_eq!(to_vec(&back), vec![0, 1, 2, 3, 4]);
+    let (front, back) = numbers(0..5).split_at(3);
+    assert_eq!(to_vec(&front), vec![0, 1, 2]);
+    assert_eq!(to_vec(&back), vec![3, 4]);
+}
+
+#[test]
+fn test_nth_last_find() {
+    let list = numbers(0..5);
+    assert_eq!(list.nth(0), Some(&0));
+    assert_eq!(list.nth(4), Some(&4));
+    assert_eq!(list.nth(5), None);
+    assert_eq!(list.last(), Some(&4));
+    assert_eq!(List::<i32>::new().last(), None);
+    assert_eq!(list.find(|n| n % 3 == 2), Some(&2));
+    assert_eq!(list.find(|n| *n > 9), None);
+}
+
+#[test]
+fn test_combinators_long_list() {
+    const LEN: i32 = 1_000_000;
+    let list = numbers(0..LEN).map(|n| n as i64);
+    let list = list
+        .filter(|n| n % 2 == 0)
+        .concat(numbers(0..10).map(i64::from));
+    assert_eq!(list.last(), Some(&9));
+    assert_eq!(list.nth(1), Some(&2));
+    let (front, back) = list.split_at(LEN as usize / 2);
+    assert_eq!(back.iter().count(), 10);
+    let list = front.reverse().zip(numbers(0..LEN));
+    assert_eq!(list.find(|&(_, i)| i == 1), Some(&((LEN as i64 - 4), 1)));
+    let sum = list.fold_right(0, |(n, _), acc| acc + n);
+    assert_eq!(sum, (0..LEN as i64).step_by(2).sum::<i64>());
+}
+
+/// Defines a persistent list type that shares its tails through `$ptr`.
+///
+/// `RcList` and `ArcList` only differ in the reference counted pointer they
+/// use, so they are both generated from this one definition.
+macro_rules! persistent_list {
+    ($(#[$attr:meta])* $list:ident, $node:ident, $ptr:ident) => {
+        $(#[$attr])*
+        pub struct $list<T> {
+            head: Option<$ptr<$node<T>>>,
+        }
+
+        struct $node<T> {
+            element: T,
+            next: Option<$ptr<$node<T>>>,
+        }
+
+        impl<T> $list<T> {
+            #[allow(clippy::new_without_default)]
+            pub fn new() -> Self {
+                $list { head: None }
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.head.is_none()
+            }
+
+            /// Returns a new list with `element` in front of this one.
+            ///
+            /// This list is left untouched and becomes the tail of the new one.
+            pub fn push(&self, element: T) -> Self {
+                $list {
+                    head: Some($ptr::new($node {
+                        element,
+                        next: self.head.clone(),
+                    })),
+                }
+            }
+
+            pub fn head(&self) -> Option<&T> {
+                self.head.as_ref().map(|node| &node.element)
+            }
+
+            /// Returns the list without its first element.
+            ///
+            /// The tail of the empty list is the empty list.
+            pub fn tail(&self) -> Self {
+                $list {
+                    head: self.head.as_ref().and_then(|node| node.next.clone()),
+                }
+            }
+        }
+
+        /// Cloning only copies the pointer to the first element.
+        impl<T> Clone for $list<T> {
+            fn clone(&self) -> Self {
+                $list {
+                    head: self.head.clone(),
+                }
+            }
+        }
+
+        impl<T> Drop for $list<T> {
+            fn drop(&mut self) {
+                // Free the nodes that only this list uses one at a time. As
+                // soon as we reach a node that another list also uses that
+                // list keeps the rest alive so we can stop.
+                //
+                // into_inner rather than try_unwrap: if two threads drop the
+                // last two lists sharing a node at once, both try_unwraps can
+                // fail and the node would then be freed by the recursive Drop.
+                let mut current = self.head.take();
+                while let Some(node) = current {
+                    match $ptr::into_inner(node) {
+                        Some(mut node) => current = node.next.take(),
+                        None => break,
+                    }
+                }
+            }
+        }
+
+        impl<T: std::fmt::Display> std::fmt::Display for $list<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                let mut first = true;
+                let mut current = &self.head;
+                while let Some(node) = current {
+                    if !first {
+                        write!(f, ", ")?;
+                    } else {
+                        first = false;
+                    }
+                    write!(f, "{}", node.element)?;
+                    current = &node.next;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+persistent_list!(
+    /// Persistent functional list.
+    ///
+    /// Unlike `List` pushing onto an `RcList` doesn't consume it, the old list
+    /// becomes the shared tail of the new one. This makes `push`, `tail` and
+    /// `clone` O(1) and lets many versions of a list share their common
+    /// suffix.
+    RcList,
+    RcNode,
+    Rc
+);
+
+persistent_list!(
+    /// Thread-safe version of `RcList`.
+    ///
+    /// This uses atomic reference counting so lists can be shared between
+    /// threads, at the cost of slightly slower `push`, `tail` and `clone`.
+    ArcList,
+    ArcNode,
+    Arc
+);
+
+#[test]
+fn test_rc_list_push() {
+    let list = RcList::new();
+    assert!(list.is_empty());
+    assert_eq!(list.head(), None);
+
+    let one = list.push(1);
+    let two = one.push(2);
+    assert!(list.is_empty());
+    assert_eq!(one.head(), Some(&1));
+    assert_eq!(two.head(), Some(&2));
+    assert_eq!(two.tail().head(), Some(&1));
+    assert!(two.tail().tail().is_empty());
+    assert!(two.tail().tail().tail().is_empty());
+}
+
+#[test]
+fn test_rc_list_shares_tail() {
+    let base = RcList::new().push(1).push(2);
+    let left = base.push(3);
+    let right = base.push(4);
+
+    let base_node = base.head.as_ref().unwrap();
+    assert!(Rc::ptr_eq(left.tail().head.as_ref().unwrap(), base_node));
+    assert!(Rc::ptr_eq(right.tail().head.as_ref().unwrap(), base_node));
+    assert!(Rc::ptr_eq(base.clone().head.as_ref().unwrap(), base_node));
+    assert_eq!(Rc::strong_count(base_node), 3);
+
+    drop(left);
+    drop(base);
+    assert_eq!(format!("{}", right), "4, 2, 1");
+}
+
+#[test]
+fn test_rc_list_fmt() {
+    let list = RcList::new();
+    assert_eq!(format!("{}", list), "");
+    let list = list.push("one").push("two").push("three");
+    assert_eq!(format!("{}", list), "three, two, one");
+}
+
+#[test]
+fn test_rc_list_drop_long_list() {
+    let mut list = RcList::new();
+    for i in 0..10_000_000 {
+        list = list.push(i);
+    }
+    let shared = list.tail();
+    drop(list);
+    assert_eq!(shared.head(), Some(&9_999_998));
+    drop(shared);
+}
+
+#[test]
+fn test_arc_list_threads() {
+    let base = ArcList::new().push(1).push(2);
+    let handles: Vec<_> = (3..6)
+        .map(|i| {
+            let base = base.clone();
+            std::thread::spawn(move || format!("{}", base.push(i)))
+        })
+        .collect();
+    let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    assert_eq!(results, vec!["3, 2, 1", "4, 2, 1", "5, 2, 1"]);
+    assert_eq!(Arc::strong_count(base.head.as_ref().unwrap()), 1);
+}
+
+#[test]
+fn test_arc_list_drop_shared_from_threads() {
+    for _ in 0..10 {
+        let mut list = ArcList::new();
+        for i in 0..100_000 {
+            list = list.push(i);
+        }
+        // Both threads drop their clone at the same time, so neither knows it
+        // holds the last reference until the count reaches zero.
+        let barrier = Arc::new(std::sync::Barrier::new(2));
+        let handles: Vec<_> = vec![list.clone(), list]
+            .into_iter()
+            .map(|list| {
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    drop(list);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
 }
\ No newline at end of file
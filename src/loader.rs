@@ -0,0 +1,701 @@
+// Loading match results from files.
+//
+// The score functions in the codelab only see Scores that we construct by hand,
+// but real results come in files. This reads them into a List of
+// standings::Game, which has the team names as well as the Score. There are two
+// formats:
+//
+// - CSV with a header row naming the columns home, away, home_points and
+//   away_points, in any order. Other columns are ignored. Fields can be quoted
+//   to include commas, with "" for a quote, but can't span lines.
+// - JSON lines, one object per line such as
+//   {"home": "Ajax", "away": "Celtic", "home_points": 2, "away_points": 1}.
+//   Other keys are ignored as long as their values aren't arrays or objects.
+//
+// Like the rng module, rather than adding dependencies for these we parse them
+// by hand. Blank lines are skipped in both.
+//
+// A bad row is reported with its line, the column where the problem starts and
+// the reason. In strict mode the first bad row fails the load, in lenient mode
+// bad rows are skipped and their errors collected.
+
+use crate::solution::{List, Score};
+use crate::standings::Game;
+use std::io::BufRead;
+
+/// The format of a match file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    JsonLines,
+}
+
+impl Format {
+    /// Guesses the format from the extension of `path`, `.csv` for CSV and
+    /// `.jsonl` or `.ndjson` for JSON lines.
+    pub fn from_path(path: &std::path::Path) -> Option<Format> {
+        match path.extension()?.to_str()? {
+            "csv" => Some(Format::Csv),
+            "jsonl" | "ndjson" => Some(Format::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+/// What to do with bad rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Fail on the first bad row.
+    Strict,
+    /// Skip bad rows and collect their errors.
+    Lenient,
+}
+
+/// The result of a successful load.
+#[derive(Debug)]
+pub struct Loaded {
+    /// The games in the order they appear in the file.
+    pub games: List<Game>,
+    /// The rows that were skipped in lenient mode.
+    pub skipped: Vec<RowError>,
+}
+
+/// A row that couldn't be loaded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowError {
+    line: usize,
+    column: usize,
+    kind: RowErrorKind,
+}
+
+/// The reason a row couldn't be loaded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RowErrorKind {
+    /// The CSV header doesn't name one of the columns we need.
+    MissingColumn(&'static str),
+    /// A CSV column or JSON key appears more than once.
+    Duplicate(String),
+    /// A CSV row has a different number of fields than the header.
+    FieldCount { expected: usize, found: usize },
+    /// A JSON object doesn't have one of the keys we need.
+    MissingField(&'static str),
+    /// A JSON value has the wrong type, such as a number for a team name.
+    WrongType {
+        field: &'static str,
+        expected: &'static str,
+    },
+    /// A team name is empty.
+    EmptyTeam,
+    /// The points aren't a whole number that fits in a u32.
+    InvalidPoints(String),
+    /// A quoted field or string has no closing quote.
+    UnterminatedQuote,
+    /// Some other malformed syntax, described by the message.
+    Syntax(&'static str),
+}
+
+impl RowError {
+    /// Returns the line of the row, starting from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column, in characters starting from 1, where the problem
+    /// starts.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns what went wrong.
+    pub fn kind(&self) -> &RowErrorKind {
+        &self.kind
+    }
+}
+
+impl std::fmt::Display for RowErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RowErrorKind::MissingColumn(column) => write!(f, "missing column {:?}", column),
+            RowErrorKind::Duplicate(name) => write!(f, "duplicate {:?}", name),
+            RowErrorKind::FieldCount { expected, found } => {
+                write!(f, "expected {} fields, found {}", expected, found)
+            }
+            RowErrorKind::MissingField(field) => write!(f, "missing field {:?}", field),
+            RowErrorKind::WrongType { field, expected } => {
+                write!(f, "{:?} should be {}", field, expected)
+            }
+            RowErrorKind::EmptyTeam => write!(f, "empty team name"),
+            RowErrorKind::InvalidPoints(text) => write!(f, "invalid points {:?}", text),
+            RowErrorKind::UnterminatedQuote => write!(f, "unterminated quote"),
+            RowErrorKind::Syntax(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::fmt::Display for RowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+impl std::error::Error for RowError {}
+
+/// An error that stopped a load.
+#[derive(Debug)]
+pub enum LoadError {
+    /// Reading failed, or the file isn't UTF-8.
+    Io(std::io::Error),
+    /// A bad row in strict mode, or a bad CSV header in either mode.
+    Row(RowError),
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "read failed: {}", e),
+            LoadError::Row(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            LoadError::Row(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(e: std::io::Error) -> Self {
+        LoadError::Io(e)
+    }
+}
+
+/// A problem in a row, and the column where it starts.
+type RowResult<T> = Result<T, (usize, RowErrorKind)>;
+
+/// Reads the games from `reader`.
+///
+/// Without a valid header none of the rows of a CSV file can be read, so a bad
+/// header fails the load even in lenient mode.
+pub fn load<R: BufRead>(reader: R, format: Format, mode: Mode) -> Result<Loaded, LoadError> {
+    let mut loaded = Loaded {
+        games: List::empty(),
+        skipped: Vec::new(),
+    };
+    let mut columns = None;
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let error = |(column, kind)| RowError {
+            line: index + 1,
+            column,
+            kind,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let game = match (format, &columns) {
+            (Format::Csv, None) => {
+                columns = Some(csv_header(&line).map_err(|e| LoadError::Row(error(e)))?);
+                continue;
+            }
+            (Format::Csv, Some(columns)) => csv_row(&line, columns),
+            (Format::JsonLines, _) => json_row(&line),
+        };
+        match (game, mode) {
+            (Ok(game), _) => loaded.games.push_back(game),
+            (Err(e), Mode::Strict) => return Err(LoadError::Row(error(e))),
+            (Err(e), Mode::Lenient) => loaded.skipped.push(error(e)),
+        }
+    }
+    Ok(loaded)
+}
+
+fn team(column: usize, name: String) -> RowResult<String> {
+    match name.is_empty() {
+        true => Err((column, RowErrorKind::EmptyTeam)),
+        false => Ok(name),
+    }
+}
+
+fn points(column: usize, text: &str) -> RowResult<u32> {
+    // u32's FromStr accepts a leading +, which neither format allows.
+    match text.starts_with('+') {
+        true => None,
+        false => text.parse().ok(),
+    }
+    .ok_or_else(|| (column, RowErrorKind::InvalidPoints(text.to_string())))
+}
+
+/// Where each column we need is in a CSV row.
+struct Columns {
+    home: usize,
+    away: usize,
+    home_points: usize,
+    away_points: usize,
+    count: usize,
+}
+
+fn csv_header(line: &str) -> RowResult<Columns> {
+    let fields = csv_fields(line)?;
+    for (i, (column, name)) in fields.iter().enumerate() {
+        if fields[..i].iter().any(|(_, other)| other == name) {
+            return Err((*column, RowErrorKind::Duplicate(name.clone())));
+        }
+    }
+    let find = |name| {
+        fields
+            .iter()
+            .position(|(_, field)| field == name)
+            .ok_or((1, RowErrorKind::MissingColumn(name)))
+    };
+    Ok(Columns {
+        home: find("home")?,
+        away: find("away")?,
+        home_points: find("home_points")?,
+        away_points: find("away_points")?,
+        count: fields.len(),
+    })
+}
+
+fn csv_row(line: &str, columns: &Columns) -> RowResult<Game> {
+    let mut fields = csv_fields(line)?;
+    if fields.len() != columns.count {
+        let kind = RowErrorKind::FieldCount {
+            expected: columns.count,
+            found: fields.len(),
+        };
+        return Err((1, kind));
+    }
+    let mut take = |i: usize| std::mem::take(&mut fields[i]);
+    let (home, away) = (take(columns.home), take(columns.away));
+    let (home_points, away_points) = (take(columns.home_points), take(columns.away_points));
+    Ok(Game {
+        home: team(home.0, home.1)?,
+        away: team(away.0, away.1)?,
+        score: Score {
+            home_points: points(home_points.0, &home_points.1)?,
+            away_points: points(away_points.0, &away_points.1)?,
+        },
+    })
+}
+
+/// Splits a CSV line into its fields, with the column each one starts at.
+///
+/// Unquoted fields have surrounding whitespace trimmed.
+fn csv_fields(line: &str) -> RowResult<Vec<(usize, String)>> {
+    let mut chars = Cursor::new(line);
+    let mut fields = Vec::new();
+    loop {
+        let start = chars.column;
+        let mut field = String::new();
+        if chars.peek() == Some('"') {
+            chars.bump();
+            loop {
+                match chars.bump() {
+                    None => return Err((start, RowErrorKind::UnterminatedQuote)),
+                    Some('"') if chars.peek() == Some('"') => {
+                        chars.bump();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                }
+            }
+            if !matches!(chars.peek(), None | Some(',')) {
+                return Err((chars.column, RowErrorKind::Syntax("expected ','")));
+            }
+        } else {
+            while let Some(c) = chars.peek().filter(|&c| c != ',') {
+                chars.bump();
+                field.push(c);
+            }
+            field = field.trim().to_string();
+        }
+        fields.push((start, field));
+        if chars.bump().is_none() {
+            return Ok(fields);
+        }
+    }
+}
+
+/// A JSON value that we don't need to look inside.
+enum Value {
+    String(String),
+    Number(String),
+    Literal,
+}
+
+fn json_row(line: &str) -> RowResult<Game> {
+    let mut json = Cursor::new(line);
+    json.expect('{', "expected '{'")?;
+
+    let mut home = None;
+    let mut away = None;
+    let mut home_points = None;
+    let mut away_points = None;
+    json.skip_whitespace();
+    if json.peek() == Some('}') {
+        json.bump();
+    } else {
+        loop {
+            json.skip_whitespace();
+            let key_column = json.column;
+            if json.peek() != Some('"') {
+                return Err((key_column, RowErrorKind::Syntax("expected a key")));
+            }
+            let key = json.string()?;
+            json.expect(':', "expected ':'")?;
+            json.skip_whitespace();
+            let value_column = json.column;
+            let value = json.value()?;
+
+            let slot = match key.as_str() {
+                "home" => Some(("home", &mut home)),
+                "away" => Some(("away", &mut away)),
+                "home_points" => Some(("home_points", &mut home_points)),
+                "away_points" => Some(("away_points", &mut away_points)),
+                // Other keys are ignored.
+                _ => None,
+            };
+            if let Some((field, slot)) = slot {
+                if slot.is_some() {
+                    return Err((key_column, RowErrorKind::Duplicate(key)));
+                }
+                *slot = Some((field, value_column, value));
+            }
+
+            json.skip_whitespace();
+            let next = json.peek();
+            if next == Some(',') || next == Some('}') {
+                json.bump();
+            }
+            match next {
+                Some(',') => continue,
+                Some('}') => break,
+                _ => return Err((json.column, RowErrorKind::Syntax("expected ',' or '}'"))),
+            }
+        }
+    }
+    json.skip_whitespace();
+    if json.peek().is_some() {
+        let kind = RowErrorKind::Syntax("unexpected text after the object");
+        return Err((json.column, kind));
+    }
+
+    fn field<T>(
+        value: Option<(&'static str, usize, Value)>,
+        name: &'static str,
+        convert: fn(&'static str, usize, Value) -> RowResult<T>,
+    ) -> RowResult<T> {
+        let (field, column, value) = value.ok_or((1, RowErrorKind::MissingField(name)))?;
+        convert(field, column, value)
+    }
+    fn string(field: &'static str, column: usize, value: Value) -> RowResult<String> {
+        match value {
+            Value::String(name) => team(column, name),
+            _ => Err((column, wrong_type(field, "a string"))),
+        }
+    }
+    fn number(field: &'static str, column: usize, value: Value) -> RowResult<u32> {
+        match value {
+            Value::Number(text) => points(column, &text),
+            _ => Err((column, wrong_type(field, "a number"))),
+        }
+    }
+    fn wrong_type(field: &'static str, expected: &'static str) -> RowErrorKind {
+        RowErrorKind::WrongType { field, expected }
+    }
+
+    Ok(Game {
+        home: field(home, "home", string)?,
+        away: field(away, "away", string)?,
+        score: Score {
+            home_points: field(home_points, "home_points", number)?,
+            away_points: field(away_points, "away_points", number)?,
+        },
+    })
+}
+
+/// Walks the characters of a line, keeping track of the column.
+struct Cursor<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    /// The column of the next character.
+    column: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(line: &'a str) -> Self {
+        Cursor {
+            chars: line.chars().peekable(),
+            column: 1,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        self.column += 1;
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, c: char, message: &'static str) -> RowResult<()> {
+        self.skip_whitespace();
+        match self.peek() == Some(c) {
+            true => {
+                self.bump();
+                Ok(())
+            }
+            false => Err((self.column, RowErrorKind::Syntax(message))),
+        }
+    }
+
+    /// Reads a JSON string, starting at its opening quote.
+    fn string(&mut self) -> RowResult<String> {
+        let start = self.column;
+        self.bump();
+        let mut text = String::new();
+        loop {
+            let column = self.column;
+            match self.bump() {
+                None => return Err((start, RowErrorKind::UnterminatedQuote)),
+                Some('"') => return Ok(text),
+                Some('\\') => text.push(self.escape(column)?),
+                Some(c) if (c as u32) < 0x20 => {
+                    let kind = RowErrorKind::Syntax("control character in string");
+                    return Err((column, kind));
+                }
+                Some(c) => text.push(c),
+            }
+        }
+    }
+
+    /// Reads the rest of an escape sequence that started at `column`.
+    fn escape(&mut self, column: usize) -> RowResult<char> {
+        let invalid = (column, RowErrorKind::Syntax("invalid escape"));
+        let c = match self.bump().ok_or_else(|| invalid.clone())? {
+            c @ ('"' | '\\' | '/') => c,
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let high = self.hex4().ok_or_else(|| invalid.clone())?;
+                // Characters outside the BMP are written as a surrogate pair.
+                let code = if (0xd800..0xdc00).contains(&high) {
+                    let low = match (self.bump(), self.bump()) {
+                        (Some('\\'), Some('u')) => self.hex4(),
+                        _ => None,
+                    };
+                    match low {
+                        Some(low) if (0xdc00..0xe000).contains(&low) => {
+                            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                        }
+                        _ => return Err(invalid),
+                    }
+                } else {
+                    high
+                };
+                return char::from_u32(code).ok_or(invalid);
+            }
+            _ => return Err(invalid),
+        };
+        Ok(c)
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        (0..4).try_fold(0, |code, _| Some(code * 16 + self.bump()?.to_digit(16)?))
+    }
+
+    /// Reads a JSON value, other than an array or object.
+    fn value(&mut self) -> RowResult<Value> {
+        let column = self.column;
+        match self.peek() {
+            Some('"') => Ok(Value::String(self.string()?)),
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let mut text = String::new();
+                while let Some(c) = self
+                    .peek()
+                    .filter(|&c| c.is_ascii_digit() || "+-.eE".contains(c))
+                {
+                    self.bump();
+                    text.push(c);
+                }
+                Ok(Value::Number(text))
+            }
+            Some('{' | '[') => Err((
+                column,
+                RowErrorKind::Syntax("arrays and objects are not supported"),
+            )),
+            Some(c) if c.is_ascii_alphabetic() => {
+                let mut word = String::new();
+                while let Some(c) = self.peek().filter(char::is_ascii_alphabetic) {
+                    self.bump();
+                    word.push(c);
+                }
+                match word.as_str() {
+                    "true" | "false" | "null" => Ok(Value::Literal),
+                    _ => Err((column, RowErrorKind::Syntax("expected a value"))),
+                }
+            }
+            _ => Err((column, RowErrorKind::Syntax("expected a value"))),
+        }
+    }
+}
+
+#[cfg(test)]
+fn games(text: &str, format: Format) -> Vec<Game> {
+    let loaded = load(text.as_bytes(), format, Mode::Strict).unwrap();
+    assert!(loaded.skipped.is_empty());
+    loaded.games.into_iter().collect()
+}
+
+#[cfg(test)]
+fn errors(text: &str, format: Format) -> Vec<String> {
+    let loaded = load(text.as_bytes(), format, Mode::Lenient).unwrap();
+    loaded.skipped.iter().map(RowError::to_string).collect()
+}
+
+#[test]
+fn test_csv() {
+    let games = games(include_str!("../fixtures/matches.csv"), Format::Csv);
+    assert_eq!(
+        games,
+        vec![
+            Game::new("Ajax", "Benfica", 3, 1),
+            Game::new("Benfica", "Celtic", 2, 2),
+            Game::new("Celtic, Glasgow", "Ajax", 1, 0),
+            Game::new("Benfica", "Ajax \"A\"", 0, 0),
+        ]
+    );
+}
+
+#[test]
+fn test_csv_errors() {
+    let text = include_str!("../fixtures/malformed.csv");
+    match load(text.as_bytes(), Format::Csv, Mode::Strict) {
+        Err(LoadError::Row(e)) => {
+            assert_eq!((e.line(), e.column()), (3, 16));
+            assert_eq!(e.kind(), &RowErrorKind::InvalidPoints("two".to_string()));
+        }
+        other => panic!("{:?}", other),
+    }
+
+    let loaded = load(text.as_bytes(), Format::Csv, Mode::Lenient).unwrap();
+    let games: Vec<_> = loaded.games.into_iter().collect();
+    assert_eq!(
+        games,
+        vec![
+            Game::new("Ajax", "Benfica", 3, 1),
+            Game::new("Celtic", "Ajax", 0, 1),
+        ]
+    );
+    assert_eq!(
+        errors(text, Format::Csv),
+        vec![
+            "line 3, column 16: invalid points \"two\"",
+            "line 4, column 1: expected 4 fields, found 3",
+            "line 5, column 1: empty team name",
+            "line 6, column 1: unterminated quote",
+            "line 7, column 15: invalid points \"99999999999\"",
+            "line 8, column 13: invalid points \"+1\"",
+            "line 9, column 7: expected ','",
+        ]
+    );
+}
+
+#[test]
+fn test_csv_header() {
+    let header = |text: &str| match load(text.as_bytes(), Format::Csv, Mode::Lenient) {
+        Err(LoadError::Row(e)) => e.to_string(),
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(
+        header("home,away,home_points\nA,B,1"),
+        "line 1, column 1: missing column \"away_points\""
+    );
+    assert_eq!(
+        header("\nhome,away,home,home_points,away_points"),
+        "line 2, column 11: duplicate \"home\""
+    );
+    // A file with only a header, or nothing at all, has no games.
+    assert!(games("home,away,home_points,away_points\n", Format::Csv).is_empty());
+    assert!(games("", Format::Csv).is_empty());
+}
+
+#[test]
+fn test_json_lines() {
+    let games = games(include_str!("../fixtures/matches.jsonl"), Format::JsonLines);
+    assert_eq!(
+        games,
+        vec![
+            Game::new("Ajax", "Benfica", 3, 1),
+            Game::new("Benfica", "Celtic", 2, 2),
+            Game::new("Celtic \"The Bhoys\"", "Ajax", 1, 0),
+            Game::new("München", "Ajax", 0, 0),
+            Game::new("Tab\tSlash/😀", "Ajax", 4294967295, 0),
+        ]
+    );
+}
+
+#[test]
+fn test_json_lines_errors() {
+    let text = include_str!("../fixtures/malformed.jsonl");
+    let loaded = load(text.as_bytes(), Format::JsonLines, Mode::Lenient).unwrap();
+    assert_eq!(loaded.games.len(), 2);
+    assert_eq!(
+        errors(text, Format::JsonLines),
+        vec![
+            "line 2, column 1: missing field \"away_points\"",
+            "line 3, column 26: \"away\" should be a string",
+            "line 4, column 52: invalid points \"-3\"",
+            "line 5, column 70: invalid points \"1.5\"",
+            "line 6, column 36: expected ',' or '}'",
+            "line 7, column 1: expected '{'",
+            "line 8, column 18: duplicate \"home\"",
+            "line 9, column 82: arrays and objects are not supported",
+            "line 10, column 71: expected ',' or '}'",
+            "line 11, column 10: empty team name",
+            "line 12, column 10: unterminated quote",
+            "line 13, column 13: invalid escape",
+            "line 14, column 11: invalid escape",
+            "line 15, column 73: unexpected text after the object",
+            "line 16, column 52: \"home_points\" should be a number",
+        ]
+    );
+}
+
+#[test]
+fn test_io_errors() {
+    let text: &[u8] = b"home,away,home_points,away_points\n\xff,B,1,2\n";
+    match load(text, Format::Csv, Mode::Lenient) {
+        Err(LoadError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidData),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn test_format_from_path() {
+    let format = |path: &str| Format::from_path(std::path::Path::new(path));
+    assert_eq!(format("scores.csv"), Some(Format::Csv));
+    assert_eq!(format("dir/scores.jsonl"), Some(Format::JsonLines));
+    assert_eq!(format("scores.ndjson"), Some(Format::JsonLines));
+    assert_eq!(format("scores.txt"), None);
+    assert_eq!(format("scores"), None);
+}
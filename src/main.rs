@@ -0,0 +1,799 @@
+// Command line tool for the score functions.
+//
+// Reads match files in the formats that the loader module supports, from the
+// paths given or from stdin, and prints the result as a table, CSV or JSON.
+//
+//     rust101 total scores.csv
+//     rust101 winner --format jsonl < scores.jsonl
+//     rust101 filter --home-wins --output csv autumn.csv spring.jsonl
+//     rust101 standings --points 2-1-0 --tiebreakers head-to-head scores.csv
+//     rust101 ratings --system glicko2 --output csv scores.csv
+//
+// The exit code says what went wrong, see the EXIT_ constants.
+
+use rust101::loader::{self, Format, LoadError, Mode};
+use rust101::ratings::{Elo, Glicko2};
+use rust101::solution::{self, List, Score, Team};
+use rust101::standings::{self, Game, Points, Rules, Tiebreaker};
+use std::io::{BufRead, Write};
+
+/// The arguments are invalid.
+const EXIT_USAGE: i32 = 2;
+/// A file couldn't be read.
+const EXIT_IO: i32 = 3;
+/// A file has a bad row, or a bad CSV header.
+const EXIT_PARSE: i32 = 4;
+/// The files have no games.
+const EXIT_EMPTY: i32 = 5;
+
+const USAGE: &str = "\
+Usage: rust101 <command> [options] [files...]
+
+Reads match files, or stdin if there are none or a file is -, and prints:
+
+  total                   the total home and away points
+  winner                  whether home or away teams scored more in total
+  filter --home-wins      the games the home team won
+  standings               the league table
+  ratings                 every team's rating before and after each game
+
+Options:
+  --format csv|jsonl      the input format, by default from the file extension
+                          and CSV for stdin
+  --output table|csv|json the output format, table by default
+  --lenient               skip bad rows with a warning instead of failing
+  --points W-D-L          table points for a win, draw and loss, 3-1-0 by
+                          default (standings only)
+  --tiebreakers LIST      comma separated from head-to-head, goal-difference
+                          and goals-for, or none (standings only)
+  --system elo|glicko2    the rating system, elo by default (ratings only)
+  --k K                   the Elo K-factor, 32 by default (ratings only)
+  --home-advantage N      Elo points added to the home team's rating when
+                          working out the expected result, 0 by default
+                          (ratings only)
+
+Exit codes: 2 invalid arguments, 3 read error, 4 bad input, 5 no games.
+";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Command {
+    Help,
+    Total,
+    Winner,
+    Filter,
+    Standings,
+    Ratings,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Output {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum System {
+    Elo,
+    Glicko2,
+}
+
+#[derive(Debug)]
+struct Options {
+    command: Command,
+    files: Vec<String>,
+    format: Option<Format>,
+    output: Output,
+    mode: Mode,
+    home_wins: bool,
+    rules: Rules,
+    system: System,
+    elo: Elo,
+}
+
+/// An error that ends the program.
+#[derive(Debug)]
+struct Failure {
+    code: i32,
+    message: String,
+}
+
+fn usage(message: String) -> Failure {
+    Failure {
+        code: EXIT_USAGE,
+        message: format!("{}\nRun rust101 --help for usage.", message),
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Options, Failure> {
+    let mut args = args.iter();
+    let command = match args.next().map(String::as_str) {
+        Some("-h" | "--help" | "help") => Command::Help,
+        Some("total") => Command::Total,
+        Some("winner") => Command::Winner,
+        Some("filter") => Command::Filter,
+        Some("standings") => Command::Standings,
+        Some("ratings") => Command::Ratings,
+        Some(other) => return Err(usage(format!("unknown command {:?}", other))),
+        None => return Err(usage("missing command".to_string())),
+    };
+    let mut options = Options {
+        command,
+        files: Vec::new(),
+        format: None,
+        output: Output::Table,
+        mode: Mode::Strict,
+        home_wins: false,
+        rules: Rules::default(),
+        system: System::Elo,
+        elo: Elo::default(),
+    };
+    // The last Elo only option, which Glicko-2 can't use.
+    let mut elo_only = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .map(String::as_str)
+                .ok_or_else(|| usage(format!("{} needs a value", arg)))
+        };
+        let only_for = |command: Command| match options.command == command {
+            true => Ok(()),
+            false => Err(usage(format!(
+                "{} only applies to {:?}",
+                arg,
+                format!("{:?}", command).to_lowercase()
+            ))),
+        };
+        match arg.as_str() {
+            "-h" | "--help" => options.command = Command::Help,
+            "--format" => {
+                options.format = Some(match value()? {
+                    "csv" => Format::Csv,
+                    "jsonl" => Format::JsonLines,
+                    other => return Err(usage(format!("unknown format {:?}", other))),
+                })
+            }
+            "--output" => {
+                options.output = match value()? {
+                    "table" => Output::Table,
+                    "csv" => Output::Csv,
+                    "json" => Output::Json,
+                    other => return Err(usage(format!("unknown output {:?}", other))),
+                }
+            }
+            "--lenient" => options.mode = Mode::Lenient,
+            "--home-wins" => {
+                only_for(Command::Filter)?;
+                options.home_wins = true;
+            }
+            "--points" => {
+                only_for(Command::Standings)?;
+                let text = value()?;
+                options.rules.points = parse_points(text)
+                    .ok_or_else(|| usage(format!("invalid points {:?}", text)))?;
+            }
+            "--tiebreakers" => {
+                only_for(Command::Standings)?;
+                options.rules.tiebreakers = parse_tiebreakers(value()?)?;
+            }
+            "--system" => {
+                only_for(Command::Ratings)?;
+                options.system = match value()? {
+                    "elo" => System::Elo,
+                    "glicko2" => System::Glicko2,
+                    other => return Err(usage(format!("unknown system {:?}", other))),
+                }
+            }
+            "--k" => {
+                only_for(Command::Ratings)?;
+                let text = value()?;
+                elo_only = Some(arg);
+                options.elo.k = parse_number(text)
+                    .filter(|&k| k >= 0.0)
+                    .ok_or_else(|| usage(format!("invalid K {:?}", text)))?;
+            }
+            "--home-advantage" => {
+                only_for(Command::Ratings)?;
+                let text = value()?;
+                elo_only = Some(arg);
+                options.elo.home_advantage = parse_number(text)
+                    .ok_or_else(|| usage(format!("invalid home advantage {:?}", text)))?;
+            }
+            "-" => options.files.push(arg.clone()),
+            _ if arg.starts_with('-') => return Err(usage(format!("unknown option {:?}", arg))),
+            _ => options.files.push(arg.clone()),
+        }
+    }
+
+    if options.command == Command::Filter && !options.home_wins {
+        return Err(usage("filter needs --home-wins".to_string()));
+    }
+    if let (System::Glicko2, Some(arg)) = (options.system, elo_only) {
+        return Err(usage(format!("{} only applies to --system elo", arg)));
+    }
+    Ok(options)
+}
+
+/// Parses points such as `3-1-0`.
+fn parse_points(text: &str) -> Option<Points> {
+    let mut parts = text.split('-').map(|part| part.parse().ok());
+    let points = Points {
+        win: parts.next()??,
+        draw: parts.next()??,
+        loss: parts.next()??,
+    };
+    match parts.next() {
+        None => Some(points),
+        Some(_) => None,
+    }
+}
+
+/// Parses a finite number such as `24` or `62.5`.
+fn parse_number(text: &str) -> Option<f64> {
+    text.parse().ok().filter(|n: &f64| n.is_finite())
+}
+
+fn parse_tiebreakers(text: &str) -> Result<Vec<Tiebreaker>, Failure> {
+    if text == "none" {
+        return Ok(Vec::new());
+    }
+    text.split(',')
+        .map(|name| match name {
+            "head-to-head" => Ok(Tiebreaker::HeadToHead),
+            "goal-difference" => Ok(Tiebreaker::GoalDifference),
+            "goals-for" => Ok(Tiebreaker::GoalsFor),
+            _ => Err(usage(format!("unknown tiebreaker {:?}", name))),
+        })
+        .collect()
+}
+
+/// Loads the games from every file, in order.
+fn read_games(
+    options: &Options,
+    stdin: &mut dyn BufRead,
+    stderr: &mut dyn Write,
+) -> Result<Vec<Game>, Failure> {
+    let stdin_only = ["-".to_string()];
+    let files = match options.files.is_empty() {
+        true => &stdin_only[..],
+        false => &options.files[..],
+    };
+
+    let mut games = Vec::new();
+    for file in files {
+        let (name, loaded) = if file == "-" {
+            let format = options.format.unwrap_or(Format::Csv);
+            ("<stdin>", loader::load(&mut *stdin, format, options.mode))
+        } else {
+            let path = std::path::Path::new(file);
+            let format = options
+                .format
+                .or_else(|| Format::from_path(path))
+                .ok_or_else(|| usage(format!("{}: unknown format, use --format", file)))?;
+            let reader = std::fs::File::open(path).map_err(|e| Failure {
+                code: EXIT_IO,
+                message: format!("{}: {}", file, e),
+            })?;
+            let reader = std::io::BufReader::new(reader);
+            (file.as_str(), loader::load(reader, format, options.mode))
+        };
+        let loaded = loaded.map_err(|e| Failure {
+            code: match e {
+                LoadError::Io(_) => EXIT_IO,
+                LoadError::Row(_) => EXIT_PARSE,
+            },
+            message: format!("{}: {}", name, e),
+        })?;
+        for skipped in &loaded.skipped {
+            // Nothing useful to do if stderr is gone.
+            let _ = writeln!(stderr, "rust101: warning: {}: {}", name, skipped);
+        }
+        games.extend(loaded.games);
+    }
+
+    if games.is_empty() {
+        return Err(Failure {
+            code: EXIT_EMPTY,
+            message: "no games".to_string(),
+        });
+    }
+    Ok(games)
+}
+
+/// A value in the output, numbers aren't quoted in JSON and are right aligned
+/// in tables.
+enum Cell {
+    Text(String),
+    Number(String),
+}
+
+impl Cell {
+    fn text(&self) -> &str {
+        match self {
+            Cell::Text(text) | Cell::Number(text) => text,
+        }
+    }
+}
+
+fn text(text: &str) -> Cell {
+    Cell::Text(text.to_string())
+}
+
+fn number<N: std::fmt::Display>(n: N) -> Cell {
+    Cell::Number(n.to_string())
+}
+
+/// The output of a command, one row per record.
+struct Records {
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Cell>>,
+}
+
+fn records(options: &Options, games: Vec<Game>) -> Records {
+    let scores = || games.iter().map(|game| game.score).collect::<List<Score>>();
+    match options.command {
+        Command::Total => {
+            // Wide, so that big totals are printed rather than panicking.
+            let total = solution::wide_total_score(&scores());
+            Records {
+                columns: vec!["home_points", "away_points"],
+                rows: vec![vec![number(total.home_points), number(total.away_points)]],
+            }
+        }
+        Command::Winner => {
+            let winner = match solution::highest_total_score(&scores()) {
+                Team::Home => "home",
+                Team::Away => "away",
+                Team::Draw => "draw",
+            };
+            Records {
+                columns: vec!["winner"],
+                rows: vec![vec![text(winner)]],
+            }
+        }
+        Command::Filter => Records {
+            columns: vec!["home", "away", "home_points", "away_points"],
+            rows: games
+                .iter()
+                .filter(|game| game.score.winner() == Team::Home)
+                .map(|game| {
+                    vec![
+                        text(&game.home),
+                        text(&game.away),
+                        number(game.score.home_points),
+                        number(game.score.away_points),
+                    ]
+                })
+                .collect(),
+        },
+        Command::Standings => Records {
+            columns: vec![
+                "position",
+                "team",
+                "played",
+                "won",
+                "drawn",
+                "lost",
+                "goals_for",
+                "goals_against",
+                "goal_difference",
+                "points",
+            ],
+            rows: standings::standings(&games, &options.rules)
+                .into_iter()
+                .enumerate()
+                .map(|(i, row)| {
+                    vec![
+                        number(i + 1),
+                        text(&row.team),
+                        number(row.played),
+                        number(row.won),
+                        number(row.drawn),
+                        number(row.lost),
+                        number(row.goals_for),
+                        number(row.goals_against),
+                        number(row.goal_difference()),
+                        number(row.points),
+                    ]
+                })
+                .collect(),
+        },
+        Command::Ratings => ratings(options, &games),
+        Command::Help => unreachable!("help has no records"),
+    }
+}
+
+fn ratings(options: &Options, games: &[Game]) -> Records {
+    let rating = |rating: f64| number(format!("{:.2}", rating));
+    match options.system {
+        System::Elo => Records {
+            columns: vec![
+                "game",
+                "home",
+                "away",
+                "home_before",
+                "home_after",
+                "away_before",
+                "away_after",
+            ],
+            rows: options
+                .elo
+                .rate(games)
+                .history
+                .into_iter()
+                .map(|change| {
+                    vec![
+                        number(change.game + 1),
+                        text(&change.home),
+                        text(&change.away),
+                        rating(change.home_before),
+                        rating(change.home_after),
+                        rating(change.away_before),
+                        rating(change.away_after),
+                    ]
+                })
+                .collect(),
+        },
+        System::Glicko2 => Records {
+            columns: vec![
+                "game",
+                "home",
+                "away",
+                "home_before",
+                "home_after",
+                "home_deviation",
+                "away_before",
+                "away_after",
+                "away_deviation",
+            ],
+            rows: Glicko2::default()
+                .rate(games)
+                .history
+                .into_iter()
+                .map(|change| {
+                    vec![
+                        number(change.game + 1),
+                        text(&change.home),
+                        text(&change.away),
+                        rating(change.home_before.rating),
+                        rating(change.home_after.rating),
+                        rating(change.home_after.deviation),
+                        rating(change.away_before.rating),
+                        rating(change.away_after.rating),
+                        rating(change.away_after.deviation),
+                    ]
+                })
+                .collect(),
+        },
+    }
+}
+
+fn print(records: &Records, output: Output, out: &mut dyn Write) -> std::io::Result<()> {
+    match output {
+        Output::Table => print_table(records, out),
+        Output::Csv => print_csv(records, out),
+        Output::Json => print_json(records, out),
+    }
+}
+
+fn print_table(records: &Records, out: &mut dyn Write) -> std::io::Result<()> {
+    let width = |i: usize| {
+        let cells = records.rows.iter().map(|row| row[i].text().chars().count());
+        cells.fold(records.columns[i].len(), usize::max)
+    };
+    let widths: Vec<usize> = (0..records.columns.len()).map(width).collect();
+    let mut line = |cells: Vec<(&str, bool)>| {
+        let mut line = String::new();
+        for (i, (text, right)) in cells.into_iter().enumerate() {
+            if i > 0 {
+                line.push_str("  ");
+            }
+            let pad = " ".repeat(widths[i] - text.chars().count());
+            match right {
+                true => line.extend([pad.as_str(), text]),
+                false => line.extend([text, pad.as_str()]),
+            }
+        }
+        writeln!(out, "{}", line.trim_end())
+    };
+
+    // Headers line up with their column's values.
+    let right: Vec<bool> = match records.rows.first() {
+        Some(row) => row.iter().map(|c| matches!(c, Cell::Number(_))).collect(),
+        None => vec![false; records.columns.len()],
+    };
+    line(records.columns.iter().copied().zip(right).collect())?;
+    for row in &records.rows {
+        line(
+            row.iter()
+                .map(|cell| (cell.text(), matches!(cell, Cell::Number(_))))
+                .collect(),
+        )?;
+    }
+    Ok(())
+}
+
+fn print_csv(records: &Records, out: &mut dyn Write) -> std::io::Result<()> {
+    fn field(text: &str) -> String {
+        let special = text.contains([',', '"', '\n', '\r']) || text.trim() != text;
+        match special {
+            true => format!("\"{}\"", text.replace('"', "\"\"")),
+            false => text.to_string(),
+        }
+    }
+    writeln!(out, "{}", records.columns.join(","))?;
+    for row in &records.rows {
+        let fields: Vec<String> = row.iter().map(|cell| field(cell.text())).collect();
+        writeln!(out, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+fn print_json(records: &Records, out: &mut dyn Write) -> std::io::Result<()> {
+    fn string(text: &str) -> String {
+        let mut json = String::from("\"");
+        for c in text.chars() {
+            match c {
+                '"' => json.push_str("\\\""),
+                '\\' => json.push_str("\\\\"),
+                '\n' => json.push_str("\\n"),
+                '\r' => json.push_str("\\r"),
+                '\t' => json.push_str("\\t"),
+                c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+                c => json.push(c),
+            }
+        }
+        json.push('"');
+        json
+    }
+
+    writeln!(out, "[")?;
+    for (i, row) in records.rows.iter().enumerate() {
+        let fields: Vec<String> = records
+            .columns
+            .iter()
+            .zip(row)
+            .map(|(column, cell)| match cell {
+                Cell::Text(text) => format!("{}: {}", string(column), string(text)),
+                Cell::Number(n) => format!("{}: {}", string(column), n),
+            })
+            .collect();
+        let comma = if i + 1 < records.rows.len() { "," } else { "" };
+        writeln!(out, "  {{{}}}{}", fields.join(", "), comma)?;
+    }
+    writeln!(out, "]")
+}
+
+/// Runs the tool and returns the exit code.
+fn run(
+    args: &[String],
+    stdin: &mut dyn BufRead,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> i32 {
+    let result = parse_args(args).and_then(|options| {
+        if options.command == Command::Help {
+            return Ok(write!(stdout, "{}", USAGE));
+        }
+        let games = read_games(&options, stdin, stderr)?;
+        Ok(print(&records(&options, games), options.output, stdout))
+    });
+    match result {
+        Ok(Ok(())) => 0,
+        Ok(Err(e)) => {
+            let _ = writeln!(stderr, "rust101: {}", e);
+            EXIT_IO
+        }
+        Err(failure) => {
+            let _ = writeln!(stderr, "rust101: {}", failure.message);
+            failure.code
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let code = run(
+        &args,
+        &mut std::io::stdin().lock(),
+        &mut std::io::stdout().lock(),
+        &mut std::io::stderr(),
+    );
+    std::process::exit(code);
+}
+
+/// Runs the tool from the crate root and returns the exit code, stdout and
+/// stderr.
+#[cfg(test)]
+fn run_with(args: &str, stdin: &str) -> (i32, String, String) {
+    let root = env!("CARGO_MANIFEST_DIR");
+    let args: Vec<String> = args
+        .split_whitespace()
+        .map(|arg| arg.replace("fixtures/", &format!("{}/fixtures/", root)))
+        .collect();
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let code = run(&args, &mut stdin.as_bytes(), &mut stdout, &mut stderr);
+    let stderr = String::from_utf8(stderr).unwrap().replace(root, "");
+    (code, String::from_utf8(stdout).unwrap(), stderr)
+}
+
+#[test]
+fn test_total_and_winner() {
+    let (code, out, _) = run_with("total fixtures/matches.csv", "");
+    assert_eq!(code, 0);
+    assert_eq!(out, "home_points  away_points\n          6            3\n");
+
+    let (code, out, _) = run_with("winner fixtures/matches.csv", "");
+    assert_eq!(code, 0);
+    assert_eq!(out, "winner\nhome\n");
+
+    let stdin = "home,away,home_points,away_points\nA,B,0,2\n";
+    assert_eq!(run_with("winner --output csv", stdin).1, "winner\naway\n");
+    assert_eq!(
+        run_with("total --output json -", stdin).1,
+        "[\n  {\"home_points\": 0, \"away_points\": 2}\n]\n"
+    );
+
+    // The home total is more than u32::MAX.
+    let (code, out, _) = run_with("total --output csv fixtures/matches.jsonl", "");
+    assert_eq!(code, 0);
+    assert_eq!(out, "home_points,away_points\n4294967301,3\n");
+    assert_eq!(
+        run_with("winner fixtures/matches.jsonl", "").1,
+        "winner\nhome\n"
+    );
+}
+
+#[test]
+fn test_filter() {
+    let (code, out, _) = run_with(
+        "filter --home-wins --output csv fixtures/matches.csv fixtures/matches.jsonl",
+        "",
+    );
+    assert_eq!(code, 0);
+    assert_eq!(
+        out,
+        "home,away,home_points,away_points\n\
+         Ajax,Benfica,3,1\n\
+         \"Celtic, Glasgow\",Ajax,1,0\n\
+         Ajax,Benfica,3,1\n\
+         \"Celtic \"\"The Bhoys\"\"\",Ajax,1,0\n\
+         Tab\tSlash/😀,Ajax,4294967295,0\n"
+    );
+
+    let (_, out, _) = run_with(
+        "filter --home-wins --output json fixtures/matches.jsonl",
+        "",
+    );
+    assert!(out.contains(r#"{"home": "Celtic \"The Bhoys\"", "away": "Ajax", "#));
+    assert!(out.contains(r#"{"home": "Tab\tSlash/😀", "#));
+}
+
+#[test]
+fn test_standings() {
+    let stdin = "home,away,home_points,away_points\n\
+                 Ajax,Benfica,3,1\n\
+                 Benfica,Celtic,2,2\n\
+                 Celtic,Ajax,1,0\n\
+                 Benfica,Ajax,0,0\n";
+    let (code, out, _) = run_with("standings", stdin);
+    assert_eq!(code, 0);
+    assert_eq!(
+        out,
+        "\
+position  team     played  won  drawn  lost  goals_for  goals_against  goal_difference  points
+       1  Celtic        2    1      1     0          3              2                1       4
+       2  Ajax          3    1      1     1          3              2                1       4
+       3  Benfica       3    0      2     1          3              5               -2       2
+"
+    );
+
+    let (_, out, _) = run_with(
+        "standings --points 2-1-0 --tiebreakers goals-for --output csv",
+        stdin,
+    );
+    // Level on points and goals for, so ordered by name.
+    assert!(out.contains("\n1,Ajax,3,1,1,1,3,2,1,3\n2,Celtic,2,1,1,0,3,2,1,3\n"));
+    let (_, out, _) = run_with("standings --tiebreakers none --output csv", stdin);
+    assert!(out.contains("1,Ajax,"));
+}
+
+#[test]
+fn test_ratings() {
+    let stdin = "home,away,home_points,away_points\n\
+                 Ajax,Benfica,3,1\n\
+                 Benfica,Celtic,2,2\n";
+    let (code, out, _) = run_with("ratings", stdin);
+    assert_eq!(code, 0);
+    assert_eq!(
+        out,
+        "\
+game  home     away     home_before  home_after  away_before  away_after
+   1  Ajax     Benfica      1500.00     1516.00      1500.00     1484.00
+   2  Benfica  Celtic       1484.00     1484.74      1500.00     1499.26
+"
+    );
+
+    // K 20 and 400 for playing at home: 20 * (1 - 10 / 11) is 1.82.
+    let (_, out, _) = run_with("ratings --k 20 --home-advantage 400 --output csv", stdin);
+    assert!(out.starts_with(
+        "game,home,away,home_before,home_after,away_before,away_after\n\
+         1,Ajax,Benfica,1500.00,1501.82,1500.00,1498.18\n"
+    ));
+
+    let (code, out, _) = run_with("ratings --system glicko2 --output csv", stdin);
+    assert_eq!(code, 0);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(
+        lines[0],
+        "game,home,away,home_before,home_after,home_deviation,\
+         away_before,away_after,away_deviation"
+    );
+    assert!(lines[1].starts_with("1,Ajax,Benfica,1500.00,"));
+    assert_eq!(lines.len(), 3);
+}
+
+#[test]
+fn test_exit_codes() {
+    let usage = |args| {
+        let (code, out, err) = run_with(args, "");
+        assert_eq!((code, out.as_str()), (EXIT_USAGE, ""), "{}", args);
+        err
+    };
+    assert!(usage("").contains("missing command"));
+    assert!(usage("sum").contains("unknown command \"sum\""));
+    assert!(usage("filter").contains("filter needs --home-wins"));
+    assert!(usage("total --home-wins").contains("--home-wins only applies to \"filter\""));
+    assert!(usage("standings --points 3-1").contains("invalid points \"3-1\""));
+    assert!(usage("standings --tiebreakers luck").contains("unknown tiebreaker \"luck\""));
+    assert!(usage("ratings --system trueskill").contains("unknown system \"trueskill\""));
+    assert!(usage("ratings --k -1").contains("invalid K \"-1\""));
+    assert!(usage("ratings --home-advantage inf").contains("invalid home advantage"));
+    assert!(usage("ratings --k 20 --system glicko2").contains("--k only applies to --system elo"));
+    assert!(usage("total --system elo").contains("--system only applies to \"ratings\""));
+    assert!(usage("total --output").contains("--output needs a value"));
+    assert!(usage("total --verbose").contains("unknown option \"--verbose\""));
+    assert!(usage("total Cargo.toml").contains("Cargo.toml: unknown format, use --format"));
+
+    let (code, out, _) = run_with("--help", "");
+    assert_eq!((code, out.as_str()), (0, USAGE));
+
+    let (code, _, err) = run_with("total fixtures/missing.csv", "");
+    assert_eq!(code, EXIT_IO);
+    assert!(
+        err.starts_with("rust101: /fixtures/missing.csv: "),
+        "{}",
+        err
+    );
+
+    let (code, out, err) = run_with("total fixtures/malformed.csv", "");
+    assert_eq!((code, out.as_str()), (EXIT_PARSE, ""));
+    assert_eq!(
+        err,
+        "rust101: /fixtures/malformed.csv: line 3, column 16: invalid points \"two\"\n"
+    );
+
+    let (code, _, err) = run_with("total", "home,away\n");
+    assert_eq!(code, EXIT_PARSE);
+    assert!(err.contains("<stdin>: line 1, column 1: missing column \"home_points\""));
+
+    for stdin in ["", "home,away,home_points,away_points\n", "\n\n"] {
+        let (code, _, err) = run_with("total", stdin);
+        assert_eq!((code, err.as_str()), (EXIT_EMPTY, "rust101: no games\n"));
+    }
+    let (code, _, _) = run_with("total --format jsonl --lenient", "not json\n");
+    assert_eq!(code, EXIT_EMPTY);
+}
+
+#[test]
+fn test_lenient() {
+    let (code, out, err) = run_with("total --lenient fixtures/malformed.jsonl", "");
+    assert_eq!(code, 0);
+    assert_eq!(out, "home_points  away_points\n          3            2\n");
+    assert_eq!(err.lines().count(), 15);
+    assert!(err.starts_with(
+        "rust101: warning: /fixtures/malformed.jsonl: line 2, column 1: missing field \"away_points\"\n"
+    ));
+}
@@ -0,0 +1,285 @@
+// Model-based tests for the list types.
+//
+// Hand-written tests like test_04_pop only check the cases we thought of. Here
+// we generate random sequences of operations, run each one against a list and
+// against a VecDeque that we trust to be correct, and compare every result.
+// When they disagree we shrink the sequence, removing operations and
+// simplifying values for as long as it still fails, so that the failure we
+// report is a minimal reproduction rather than 60 random operations.
+//
+// The tests use a fixed seed so that they are reproducible. Set
+// RUST101_MODEL_SEED to run them with a different one, the seed is included in
+// every failure message.
+
+use crate::rng::Rng;
+use crate::sequence::Sequence;
+use crate::{linkedlist, solution};
+use std::collections::VecDeque;
+
+const SEED_VAR: &str = "RUST101_MODEL_SEED";
+const DEFAULT_SEED: u64 = 0x5eed;
+
+/// The number of sequences to run against each list.
+const CASES: usize = 500;
+
+/// The most operations in a generated sequence.
+const MAX_OPS: u64 = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Push(u32),
+    Pop,
+    IsEmpty,
+    Iterate,
+}
+
+/// What an operation returned.
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Pushed,
+    Popped(Option<u32>),
+    IsEmpty(bool),
+    Elements(Vec<u32>),
+}
+
+/// A list that we can run operations against.
+///
+/// Lists push and pop at the front, so the model is a VecDeque used from the
+/// front.
+trait Subject {
+    fn empty() -> Self;
+    fn apply(&mut self, op: Op) -> Outcome;
+}
+
+impl<S: Sequence<u32>> Subject for S {
+    fn empty() -> Self {
+        std::iter::empty().collect()
+    }
+
+    fn apply(&mut self, op: Op) -> Outcome {
+        match op {
+            Op::Push(n) => {
+                self.push(n);
+                Outcome::Pushed
+            }
+            Op::Pop => Outcome::Popped(self.pop()),
+            Op::IsEmpty => Outcome::IsEmpty(self.is_empty()),
+            Op::Iterate => Outcome::Elements(self.iter().copied().collect()),
+        }
+    }
+}
+
+/// The reference model.
+impl Subject for VecDeque<u32> {
+    fn empty() -> Self {
+        VecDeque::new()
+    }
+
+    fn apply(&mut self, op: Op) -> Outcome {
+        match op {
+            Op::Push(n) => {
+                self.push_front(n);
+                Outcome::Pushed
+            }
+            Op::Pop => Outcome::Popped(self.pop_front()),
+            Op::IsEmpty => Outcome::IsEmpty(self.is_empty()),
+            Op::Iterate => Outcome::Elements(self.iter().copied().collect()),
+        }
+    }
+}
+
+/// The first operation for which a list and the model disagreed.
+#[derive(Debug)]
+struct Failure {
+    step: usize,
+    op: Op,
+    expected: Outcome,
+    actual: Outcome,
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "step {}: {:?} returned {:?}, expected {:?}",
+            self.step, self.op, self.actual, self.expected
+        )
+    }
+}
+
+/// Runs `ops` against a new `S` and the model, stopping at the first
+/// difference.
+fn run<S: Subject>(ops: &[Op]) -> Result<(), Failure> {
+    let mut list = S::empty();
+    let mut model = VecDeque::empty();
+    for (step, &op) in ops.iter().enumerate() {
+        let expected = model.apply(op);
+        let actual = list.apply(op);
+        if actual != expected {
+            return Err(Failure {
+                step,
+                op,
+                expected,
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns a random sequence of operations.
+fn generate(rng: &mut Rng) -> Vec<Op> {
+    let len = rng.below(MAX_OPS + 1);
+    (0..len)
+        .map(|_| match rng.below(8) {
+            // Push a bit more often than pop so that the lists get long.
+            0..=3 => Op::Push(rng.below(1000) as u32),
+            4 | 5 => Op::Pop,
+            6 => Op::IsEmpty,
+            _ => Op::Iterate,
+        })
+        .collect()
+}
+
+/// Returns a smaller sequence for which `fails` still returns true.
+///
+/// First we try removing runs of operations, starting with the whole sequence
+/// and halving the run length down to single operations. Then we try pushing
+/// smaller values. We repeat both until neither makes progress.
+fn shrink<F: Fn(&[Op]) -> bool>(mut ops: Vec<Op>, fails: F) -> Vec<Op> {
+    loop {
+        let mut progress = false;
+
+        let mut run = ops.len();
+        while run > 0 {
+            let mut start = 0;
+            while start < ops.len() {
+                let mut candidate = ops.clone();
+                candidate.drain(start..(start + run).min(ops.len()));
+                if fails(&candidate) {
+                    ops = candidate;
+                    progress = true;
+                } else {
+                    start += run;
+                }
+            }
+            run /= 2;
+        }
+
+        for i in 0..ops.len() {
+            if let Op::Push(n) = ops[i] {
+                for smaller in [0, n / 2, n.saturating_sub(1)] {
+                    if smaller >= n {
+                        continue;
+                    }
+                    let mut candidate = ops.clone();
+                    candidate[i] = Op::Push(smaller);
+                    if fails(&candidate) {
+                        ops = candidate;
+                        progress = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        if !progress {
+            return ops;
+        }
+    }
+}
+
+fn seed() -> u64 {
+    match std::env::var(SEED_VAR) {
+        Ok(seed) => seed
+            .parse()
+            .unwrap_or_else(|_| panic!("{} must be a u64, not {:?}", SEED_VAR, seed)),
+        Err(_) => DEFAULT_SEED,
+    }
+}
+
+/// Runs random sequences against `S`, and panics with a shrunk reproduction if
+/// any of them fail.
+fn check<S: Subject>() {
+    let seed = seed();
+    let mut rng = Rng::new(seed);
+    for case in 0..CASES {
+        let ops = generate(&mut rng);
+        if run::<S>(&ops).is_ok() {
+            continue;
+        }
+        let ops = shrink(ops, |ops| run::<S>(ops).is_err());
+        let failure = run::<S>(&ops).unwrap_err();
+        panic!(
+            "case {} for seed {} ({}={} to rerun) failed, shrunk to {:?}\n{}",
+            case, seed, SEED_VAR, seed, ops, failure
+        );
+    }
+}
+
+#[test]
+fn test_solution_list() {
+    check::<solution::List<u32>>();
+}
+
+#[test]
+fn test_linkedlist_list() {
+    check::<linkedlist::List<u32>>();
+}
+
+#[test]
+fn test_generate() {
+    let mut rng = Rng::new(DEFAULT_SEED);
+    let cases: Vec<_> = (0..100).map(|_| generate(&mut rng)).collect();
+    assert!(cases.iter().any(|ops| ops.is_empty()));
+    assert!(cases.iter().any(|ops| ops.len() as u64 == MAX_OPS));
+    for op in [Op::Pop, Op::IsEmpty, Op::Iterate] {
+        assert!(cases.iter().flatten().any(|&o| o == op));
+    }
+    let mut again = Rng::new(DEFAULT_SEED);
+    assert_eq!(generate(&mut again), cases[0]);
+}
+
+// A list that loses every push after its third element.
+struct ForgetfulList(VecDeque<u32>);
+
+impl Subject for ForgetfulList {
+    fn empty() -> Self {
+        ForgetfulList(VecDeque::new())
+    }
+
+    fn apply(&mut self, op: Op) -> Outcome {
+        match op {
+            Op::Push(_) if self.0.len() >= 3 => Outcome::Pushed,
+            _ => self.0.apply(op),
+        }
+    }
+}
+
+#[test]
+fn test_shrinks_to_minimal_case() {
+    let mut rng = Rng::new(DEFAULT_SEED);
+    let ops = (0..CASES)
+        .map(|_| generate(&mut rng))
+        .find(|ops| run::<ForgetfulList>(ops).is_err())
+        .expect("no failing case found");
+    assert!(ops.len() > 5);
+
+    let shrunk = shrink(ops, |ops| run::<ForgetfulList>(ops).is_err());
+    // The bug needs four pushes and an operation that notices the fourth is
+    // missing, and removing any one operation hides it.
+    assert_eq!(shrunk.len(), 5, "{:?}", shrunk);
+    assert_eq!(shrunk[..3], [Op::Push(0); 3]);
+    for i in 0..shrunk.len() {
+        let mut smaller = shrunk.clone();
+        smaller.remove(i);
+        assert!(run::<ForgetfulList>(&smaller).is_ok());
+    }
+    assert_eq!(run::<ForgetfulList>(&shrunk).unwrap_err().step, 4);
+}
+
+#[test]
+#[should_panic(expected = "shrunk to [Push(0), Push(0), Push(0), Push(")]
+fn test_check_reports_shrunk_case() {
+    check::<ForgetfulList>();
+}
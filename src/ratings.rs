@@ -0,0 +1,468 @@
+// Ratings.
+//
+// The standings only count results. A rating also takes into account who each
+// result was against: beating a strong team moves a rating further than
+// beating a weak one. Games are replayed in order, and each one updates the
+// ratings of its two teams, so the order of the games matters.
+//
+// There are two systems:
+//
+// - Elo, where a rating is a single number and every game moves it by up to K.
+// - Glicko-2, where a rating also has a deviation, how uncertain it is, and a
+//   volatility, how erratic the team's results are. Ratings with a big
+//   deviation move further, and the deviation shrinks as a team plays.
+//
+// Both return the rating of each team before and after every game, so the
+// history can be exported as well as the final ratings.
+
+use crate::solution::Team;
+use crate::standings::Game;
+use std::collections::BTreeMap;
+
+/// The ratings of the two teams in a game, before and after it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change<R> {
+    /// The index of the game in the games that were rated.
+    pub game: usize,
+    pub home: String,
+    pub away: String,
+    pub home_before: R,
+    pub home_after: R,
+    pub away_before: R,
+    pub away_after: R,
+}
+
+/// The result of rating a list of games.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ratings<R> {
+    /// One Change per game, in the order of the games.
+    pub history: Vec<Change<R>>,
+    /// Every team's rating after the last game.
+    pub teams: BTreeMap<String, R>,
+}
+
+impl<R: Copy> Ratings<R> {
+    /// Returns the teams, highest `key` first and then by name.
+    fn ranked_by(&self, key: impl Fn(&R) -> f64) -> Vec<(&str, R)> {
+        let mut ranking: Vec<(&str, R)> = self
+            .teams
+            .iter()
+            .map(|(team, rating)| (team.as_str(), *rating))
+            .collect();
+        // Stable, so equal ratings stay in name order.
+        ranking.sort_by(|a, b| key(&b.1).total_cmp(&key(&a.1)));
+        ranking
+    }
+}
+
+impl Ratings<f64> {
+    /// Returns the teams, highest rated first.
+    pub fn ranking(&self) -> Vec<(&str, f64)> {
+        self.ranked_by(|&rating| rating)
+    }
+}
+
+impl Ratings<Rating> {
+    /// Returns the teams, highest rated first.
+    pub fn ranking(&self) -> Vec<(&str, Rating)> {
+        self.ranked_by(|rating| rating.rating)
+    }
+}
+
+/// Replays `games` in order, starting every team at `initial`, and calling
+/// `update` with the ratings of the two teams and the result to get their new
+/// ratings.
+fn replay<'a, R, I, F>(games: I, initial: R, mut update: F) -> Ratings<R>
+where
+    R: Copy,
+    I: IntoIterator<Item = &'a Game>,
+    F: FnMut(R, R, Team) -> (R, R),
+{
+    let mut teams = BTreeMap::new();
+    let mut history = Vec::new();
+    for (index, game) in games.into_iter().enumerate() {
+        let home_before = *teams.entry(game.home.clone()).or_insert(initial);
+        let away_before = *teams.entry(game.away.clone()).or_insert(initial);
+        let (home_after, away_after) = update(home_before, away_before, game.score.winner());
+        teams.insert(game.home.clone(), home_after);
+        teams.insert(game.away.clone(), away_after);
+        history.push(Change {
+            game: index,
+            home: game.home.clone(),
+            away: game.away.clone(),
+            home_before,
+            home_after,
+            away_before,
+            away_after,
+        });
+    }
+    Ratings { history, teams }
+}
+
+/// Returns what `team` scored in a game with `result`: 1 for a win, 0.5 for a
+/// draw and 0 for a loss.
+fn outcome(result: Team, team: Team) -> f64 {
+    if result == Team::Draw {
+        0.5
+    } else if result == team {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Settings for Elo ratings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Elo {
+    /// The most a rating can change in one game.
+    pub k: f64,
+    /// Added to the home team's rating when working out the expected result.
+    pub home_advantage: f64,
+    /// The rating of a team before its first game.
+    pub initial: f64,
+}
+
+/// K of 32, no home advantage and 1500 to start, the values usually used for
+/// chess.
+impl Default for Elo {
+    fn default() -> Self {
+        Elo {
+            k: 32.0,
+            home_advantage: 0.0,
+            initial: 1500.0,
+        }
+    }
+}
+
+impl Elo {
+    /// Returns the expected score, between 0 and 1, of a team rated `rating`
+    /// against one rated `opponent`.
+    pub fn expected(rating: f64, opponent: f64) -> f64 {
+        1.0 / (1.0 + 10f64.powf((opponent - rating) / 400.0))
+    }
+
+    /// Returns the new home and away ratings after a game with `result`.
+    ///
+    /// The home team gains what the away team loses.
+    pub fn update(&self, home: f64, away: f64, result: Team) -> (f64, f64) {
+        let expected = Elo::expected(home + self.home_advantage, away);
+        let change = self.k * (outcome(result, Team::Home) - expected);
+        (home + change, away - change)
+    }
+
+    /// Rates the teams in `games`, which are in the order they were played.
+    pub fn rate<'a, I: IntoIterator<Item = &'a Game>>(&self, games: I) -> Ratings<f64> {
+        replay(games, self.initial, |home, away, result| {
+            self.update(home, away, result)
+        })
+    }
+}
+
+/// The Glicko-2 ratings use a different scale internally.
+const GLICKO2_SCALE: f64 = 173.7178;
+
+/// How far apart two volatilities can be to count as equal.
+const VOLATILITY_TOLERANCE: f64 = 0.000001;
+
+/// A Glicko-2 rating.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rating {
+    pub rating: f64,
+    /// How uncertain `rating` is. The team's true rating is within about two
+    /// deviations of `rating`.
+    pub deviation: f64,
+    /// How much the team's performance varies.
+    pub volatility: f64,
+}
+
+/// Settings for Glicko-2 ratings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Glicko2 {
+    /// The rating of a team before its first game.
+    pub initial: Rating,
+    /// Limits how quickly the volatility changes, usually between 0.3 and 1.2.
+    pub tau: f64,
+}
+
+/// 1500 with a deviation of 350 and a volatility of 0.06 to start, and a tau of
+/// 0.5, the values Glickman suggests.
+impl Default for Glicko2 {
+    fn default() -> Self {
+        Glicko2 {
+            initial: Rating {
+                rating: 1500.0,
+                deviation: 350.0,
+                volatility: 0.06,
+            },
+            tau: 0.5,
+        }
+    }
+}
+
+impl Glicko2 {
+    /// Returns `player`'s rating after a rating period in which they played
+    /// `results`, each the opponent's rating and what `player` scored against
+    /// them: 1 for a win, 0.5 for a draw and 0 for a loss.
+    ///
+    /// Without any results only the deviation changes, it grows.
+    ///
+    /// This is the algorithm from Glickman's "Example of the Glicko-2 system".
+    pub fn update(&self, player: Rating, results: &[(Rating, f64)]) -> Rating {
+        let mu = (player.rating - 1500.0) / GLICKO2_SCALE;
+        let phi = player.deviation / GLICKO2_SCALE;
+        let sigma = player.volatility;
+        if results.is_empty() {
+            return Rating {
+                deviation: (phi * phi + sigma * sigma).sqrt() * GLICKO2_SCALE,
+                ..player
+            };
+        }
+
+        // Step 3 and 4: the estimated variance of the rating from the results
+        // alone, and the improvement the results suggest.
+        let mut inverse_variance = 0.0;
+        let mut improvement = 0.0;
+        for &(opponent, score) in results {
+            let mu_j = (opponent.rating - 1500.0) / GLICKO2_SCALE;
+            let g = g(opponent.deviation / GLICKO2_SCALE);
+            let expected = 1.0 / (1.0 + (-g * (mu - mu_j)).exp());
+            inverse_variance += g * g * expected * (1.0 - expected);
+            improvement += g * (score - expected);
+        }
+        let v = 1.0 / inverse_variance;
+        let delta = v * improvement;
+
+        // Step 5: the new volatility.
+        let sigma = self.volatility(phi, sigma, v, delta);
+
+        // Step 6 to 8: the new deviation and rating.
+        let phi_star = (phi * phi + sigma * sigma).sqrt();
+        let phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / v).sqrt();
+        let mu = mu + phi * phi * improvement;
+        Rating {
+            rating: mu * GLICKO2_SCALE + 1500.0,
+            deviation: phi * GLICKO2_SCALE,
+            volatility: sigma,
+        }
+    }
+
+    /// Returns the new volatility, found with the Illinois algorithm.
+    fn volatility(&self, phi: f64, sigma: f64, v: f64, delta: f64) -> f64 {
+        let tau = self.tau;
+        let a = (sigma * sigma).ln();
+        let f = |x: f64| {
+            let ex = x.exp();
+            let denominator = phi * phi + v + ex;
+            ex * (delta * delta - phi * phi - v - ex) / (2.0 * denominator * denominator)
+                - (x - a) / (tau * tau)
+        };
+
+        let mut low = a;
+        let mut high = if delta * delta > phi * phi + v {
+            (delta * delta - phi * phi - v).ln()
+        } else {
+            let mut k = 1.0;
+            while f(a - k * tau) < 0.0 {
+                k += 1.0;
+            }
+            a - k * tau
+        };
+        let mut f_low = f(low);
+        let mut f_high = f(high);
+        while (high - low).abs() > VOLATILITY_TOLERANCE {
+            let c = low + (low - high) * f_low / (f_high - f_low);
+            let f_c = f(c);
+            if f_c * f_high <= 0.0 {
+                low = high;
+                f_low = f_high;
+            } else {
+                f_low /= 2.0;
+            }
+            high = c;
+            f_high = f_c;
+        }
+        (low / 2.0).exp()
+    }
+
+    /// Rates the teams in `games`, which are in the order they were played.
+    ///
+    /// Each game is a rating period of its own for the two teams in it. The
+    /// deviations of the teams that didn't play are left alone.
+    pub fn rate<'a, I: IntoIterator<Item = &'a Game>>(&self, games: I) -> Ratings<Rating> {
+        replay(games, self.initial, |home, away, result| {
+            (
+                self.update(home, &[(away, outcome(result, Team::Home))]),
+                self.update(away, &[(home, outcome(result, Team::Away))]),
+            )
+        })
+    }
+}
+
+/// Reduces the impact of a game against an opponent with deviation `phi`.
+fn g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi * phi / (std::f64::consts::PI * std::f64::consts::PI)).sqrt()
+}
+
+#[cfg(test)]
+fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "{} is not within {} of {}",
+        actual,
+        tolerance,
+        expected
+    );
+}
+
+#[test]
+fn test_elo_expected() {
+    // The example on the Elo rating system Wikipedia page: a player rated 1613
+    // scores 2.5 against these five opponents, and is expected to score 2.88.
+    let opponents = [1609.0, 1477.0, 1388.0, 1586.0, 1720.0];
+    let expected = [0.51, 0.69, 0.79, 0.54, 0.35];
+    for (&opponent, &expected) in opponents.iter().zip(&expected) {
+        assert_close(Elo::expected(1613.0, opponent), expected, 0.005);
+    }
+    let total: f64 = opponents.iter().map(|&o| Elo::expected(1613.0, o)).sum();
+    assert_close(total, 2.88, 0.015);
+    // With K = 32 the new rating is 1601.
+    assert_eq!((1613.0 + 32.0 * (2.5 - total)).round(), 1601.0);
+
+    assert_eq!(Elo::expected(1500.0, 1500.0), 0.5);
+    assert_close(Elo::expected(1900.0, 1500.0), 10.0 / 11.0, 1e-12);
+}
+
+#[test]
+fn test_elo_update() {
+    let elo = Elo::default();
+    assert_eq!(elo.update(1500.0, 1500.0, Team::Home), (1516.0, 1484.0));
+    assert_eq!(elo.update(1500.0, 1500.0, Team::Away), (1484.0, 1516.0));
+    assert_eq!(elo.update(1500.0, 1500.0, Team::Draw), (1500.0, 1500.0));
+
+    // A home advantage of 400 makes the home team 10 times as likely to win, so
+    // a home win is worth less and a draw costs the home team.
+    let elo = Elo {
+        k: 22.0,
+        home_advantage: 400.0,
+        initial: 1500.0,
+    };
+    let check = |result, (home, away): (f64, f64)| {
+        let (new_home, new_away) = elo.update(1500.0, 1500.0, result);
+        assert_close(new_home, home, 1e-9);
+        assert_close(new_away, away, 1e-9);
+    };
+    check(Team::Home, (1502.0, 1498.0));
+    check(Team::Draw, (1491.0, 1509.0));
+    check(Team::Away, (1480.0, 1520.0));
+}
+
+#[test]
+fn test_elo_rate() {
+    let games = vec![
+        Game::new("Ajax", "Benfica", 3, 1),
+        Game::new("Benfica", "Celtic", 2, 2),
+        Game::new("Celtic", "Ajax", 1, 0),
+    ];
+    let ratings = Elo::default().rate(&games);
+
+    assert_eq!(ratings.history.len(), 3);
+    assert_eq!(
+        ratings.history[0],
+        Change {
+            game: 0,
+            home: "Ajax".to_string(),
+            away: "Benfica".to_string(),
+            home_before: 1500.0,
+            home_after: 1516.0,
+            away_before: 1500.0,
+            away_after: 1484.0,
+        }
+    );
+    // Benfica were expected to lose to Celtic, so the draw gains them points.
+    let draw = &ratings.history[1];
+    assert_eq!((draw.home_before, draw.away_before), (1484.0, 1500.0));
+    assert!(draw.home_after > 1484.0);
+    assert_eq!(draw.home_after - 1484.0, 1500.0 - draw.away_after);
+    // Each game starts from the ratings the last one left.
+    let last = &ratings.history[2];
+    assert_eq!(last.home_before, draw.away_after);
+    assert_eq!(last.away_before, 1516.0);
+
+    let ranking = ratings.ranking();
+    let teams: Vec<&str> = ranking.iter().map(|&(team, _)| team).collect();
+    assert_eq!(teams, vec!["Celtic", "Ajax", "Benfica"]);
+    assert_eq!(ranking[0].1, last.home_after);
+    // Elo moves points between teams, so the total stays the same.
+    assert_close(ratings.teams.values().sum(), 4500.0, 1e-9);
+
+    assert_eq!(
+        Elo::default().rate(&[]),
+        Ratings {
+            history: Vec::new(),
+            teams: BTreeMap::new(),
+        }
+    );
+}
+
+#[test]
+fn test_glicko2_reference() {
+    // The worked example in Glickman's "Example of the Glicko-2 system".
+    let rating = |rating, deviation| Rating {
+        rating,
+        deviation,
+        volatility: 0.06,
+    };
+    let player = rating(1500.0, 200.0);
+    let results = [
+        (rating(1400.0, 30.0), 1.0),
+        (rating(1550.0, 100.0), 0.0),
+        (rating(1700.0, 300.0), 0.0),
+    ];
+    let updated = Glicko2::default().update(player, &results);
+    assert_close(updated.rating, 1464.06, 0.01);
+    assert_close(updated.deviation, 151.52, 0.01);
+    assert_close(updated.volatility, 0.05999, 0.00001);
+
+    // Without games the deviation grows by the volatility.
+    let idle = Glicko2::default().update(player, &[]);
+    assert_eq!(idle.rating, 1500.0);
+    assert_close(idle.deviation, 200.27, 0.01);
+    assert_eq!(idle.volatility, 0.06);
+}
+
+#[test]
+fn test_glicko2_rate() {
+    let games = vec![
+        Game::new("Ajax", "Benfica", 3, 1),
+        Game::new("Ajax", "Celtic", 0, 0),
+        Game::new("Ajax", "Benfica", 2, 0),
+    ];
+    let ratings = Glicko2::default().rate(&games);
+    let first = &ratings.history[0];
+    assert_eq!(first.home_before, Glicko2::default().initial);
+    // Equal ratings, so the winner gains what the loser loses.
+    assert_close(
+        first.home_after.rating - 1500.0,
+        1500.0 - first.away_after.rating,
+        1e-9,
+    );
+    assert!(first.home_after.rating > 1600.0);
+    assert!(first.home_after.deviation < 350.0);
+    assert_eq!(first.home_after.deviation, first.away_after.deviation);
+
+    // The deviation shrinks with every game played.
+    let ajax: Vec<f64> = ratings
+        .history
+        .iter()
+        .map(|change| change.home_after.deviation)
+        .collect();
+    assert!(ajax.windows(2).all(|pair| pair[1] < pair[0]), "{:?}", ajax);
+    // Drawing with a stronger team gains rating.
+    let draw = &ratings.history[1];
+    assert!(draw.away_after.rating > draw.away_before.rating);
+    assert!(draw.home_after.rating < draw.home_before.rating);
+
+    let teams: Vec<&str> = ratings.ranking().iter().map(|&(team, _)| team).collect();
+    assert_eq!(teams, vec!["Ajax", "Celtic", "Benfica"]);
+    assert_eq!(ratings.teams["Benfica"], ratings.history[2].away_after);
+}
@@ -0,0 +1,81 @@
+// Small pseudo-random number generator.
+//
+// The crate has no dependencies, so rather than pulling in the rand crate for
+// the few places that need randomness we use SplitMix64. It is tiny, fast and
+// good enough for tests and randomized data structures, but it is not
+// cryptographically secure. The same seed always produces the same sequence,
+// which keeps failures reproducible.
+
+/// Seedable pseudo-random number generator.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator that produces the sequence for `seed`.
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// Returns the next random u64.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random number in `0..n`.
+    ///
+    /// Panics if `n` is 0.
+    #[cfg(test)]
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "Rng::below(0)");
+        // Multiply-shift maps the u64 onto the range with negligible bias for
+        // the small ranges we use.
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    /// Returns a random element of `items`.
+    ///
+    /// Panics if `items` is empty.
+    #[cfg(test)]
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+#[test]
+fn test_deterministic() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    let mut c = Rng::new(43);
+    let a: Vec<_> = (0..10).map(|_| a.next_u64()).collect();
+    let b: Vec<_> = (0..10).map(|_| b.next_u64()).collect();
+    let c: Vec<_> = (0..10).map(|_| c.next_u64()).collect();
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn test_reference_values() {
+    // The first outputs of SplitMix64 seeded with 0.
+    let mut rng = Rng::new(0);
+    assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+    assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+}
+
+#[test]
+fn test_below() {
+    let mut rng = Rng::new(1);
+    let mut counts = [0; 6];
+    for _ in 0..6000 {
+        counts[rng.below(6) as usize] += 1;
+    }
+    for &count in &counts {
+        assert!((800..1200).contains(&count), "{:?}", counts);
+    }
+    assert_eq!(rng.below(1), 0);
+}
@@ -0,0 +1,204 @@
+// A common interface for our list types.
+//
+// solution::List, linkedlist::List and Vec all store a sequence of elements,
+// but each has its own API. The Sequence trait covers what they have in common
+// so that code such as the score functions in the codelab can be written once
+// and run over any of them.
+
+use crate::{linkedlist, solution};
+
+/// A container of elements that can be used as a stack and iterated.
+///
+/// `push` and `pop` work on whichever end of the container is cheap, so the
+/// last element pushed is the first one popped. Iterating, with `iter` or
+/// `into_iter`, visits the elements in the container's own order, and
+/// `from_iter` builds a container that iterates in the same order as its input.
+pub trait Sequence<T>: IntoIterator<Item = T> + std::iter::FromIterator<T> {
+    /// The type returned by `iter`.
+    type Iter<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    /// Returns an iterator over references to the elements.
+    fn iter(&self) -> Self::Iter<'_>;
+
+    /// Adds an element.
+    fn push(&mut self, element: T);
+
+    /// Removes the most recently pushed element and returns it.
+    fn pop(&mut self) -> Option<T>;
+
+    /// Returns true iff there are no elements.
+    fn is_empty(&self) -> bool;
+}
+
+/// Pushes and pops at the back.
+impl<T> Sequence<T> for Vec<T> {
+    type Iter<'a>
+        = std::slice::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.as_slice().iter()
+    }
+
+    fn push(&mut self, element: T) {
+        Vec::push(self, element)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        Vec::pop(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        Vec::is_empty(self)
+    }
+}
+
+/// Pushes and pops at the front.
+impl<T> Sequence<T> for solution::List<T> {
+    type Iter<'a>
+        = solution::ReferenceIter<'a, T>
+    where
+        T: 'a;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.into_iter()
+    }
+
+    fn push(&mut self, element: T) {
+        solution::List::push(self, element)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        solution::List::pop(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        solution::List::is_empty(self)
+    }
+}
+
+/// Pushes and pops at the head.
+impl<T> Sequence<T> for linkedlist::List<T> {
+    type Iter<'a>
+        = linkedlist::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        linkedlist::List::iter(self)
+    }
+
+    // The inherent push and pop take the list by value, so we swap it out,
+    // and put the result back.
+
+    fn push(&mut self, element: T) {
+        let list = std::mem::replace(self, linkedlist::List::Empty);
+        *self = list.push(element);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let list = std::mem::replace(self, linkedlist::List::Empty);
+        let (tail, element) = list.pop()?;
+        *self = tail;
+        Some(element)
+    }
+
+    fn is_empty(&self) -> bool {
+        linkedlist::List::is_empty(self)
+    }
+}
+
+// Conformance tests, every implementation must pass all of them.
+
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+struct NonCopyable(u32);
+
+#[cfg(test)]
+fn check_push_pop<S: Sequence<NonCopyable>>() {
+    let mut seq: S = std::iter::empty().collect();
+    assert!(seq.is_empty());
+    assert_eq!(seq.pop(), None);
+
+    seq.push(NonCopyable(1));
+    seq.push(NonCopyable(2));
+    assert!(!seq.is_empty());
+    assert_eq!(seq.pop(), Some(NonCopyable(2)));
+    seq.push(NonCopyable(3));
+    assert_eq!(seq.pop(), Some(NonCopyable(3)));
+    assert_eq!(seq.pop(), Some(NonCopyable(1)));
+    assert_eq!(seq.pop(), None);
+    assert!(seq.is_empty());
+}
+
+#[cfg(test)]
+fn check_iteration_order<S: Sequence<NonCopyable>>() {
+    let seq: S = (0..5).map(NonCopyable).collect();
+    let refs: Vec<u32> = seq.iter().map(|n| n.0).collect();
+    assert_eq!(refs, vec![0, 1, 2, 3, 4]);
+    // iter can be called more than once.
+    assert_eq!(seq.iter().count(), 5);
+    let owned: Vec<NonCopyable> = seq.into_iter().collect();
+    assert_eq!(owned, (0..5).map(NonCopyable).collect::<Vec<_>>());
+}
+
+#[cfg(test)]
+fn check_push_and_iterate<S: Sequence<NonCopyable>>() {
+    // Whichever end push uses, pop takes from the same one, so popping every
+    // element gives them back starting from that end.
+    let mut seq: S = (0..3).map(NonCopyable).collect();
+    seq.push(NonCopyable(3));
+    let mut order: Vec<u32> = seq.iter().map(|n| n.0).collect();
+    if order.first() == Some(&3) {
+        assert_eq!(order, vec![3, 0, 1, 2]);
+    } else {
+        assert_eq!(order, vec![0, 1, 2, 3]);
+        order.reverse();
+    }
+    assert_eq!(seq.pop(), Some(NonCopyable(3)));
+    for &n in &order[1..] {
+        assert_eq!(seq.pop(), Some(NonCopyable(n)));
+    }
+    assert_eq!(seq.pop(), None);
+    assert_eq!(seq.iter().next(), None);
+}
+
+#[cfg(test)]
+fn check_long<S: Sequence<u32>>() {
+    let mut seq: S = (0..1_000_000).collect();
+    assert_eq!(seq.iter().map(|&n| n as u64).sum::<u64>(), 499_999_500_000);
+    for i in 0..1_000_000 {
+        seq.push(i);
+    }
+    for _ in 0..1_000_000 {
+        seq.pop();
+    }
+    assert_eq!(seq.into_iter().count(), 1_000_000);
+}
+
+#[cfg(test)]
+fn check_conformance<S: Sequence<NonCopyable>, L: Sequence<u32>>() {
+    check_push_pop::<S>();
+    check_iteration_order::<S>();
+    check_push_and_iterate::<S>();
+    check_long::<L>();
+}
+
+#[test]
+fn test_vec() {
+    check_conformance::<Vec<_>, Vec<_>>();
+}
+
+#[test]
+fn test_solution_list() {
+    check_conformance::<solution::List<_>, solution::List<_>>();
+}
+
+#[test]
+fn test_linkedlist_list() {
+    check_conformance::<linkedlist::List<_>, linkedlist::List<_>>();
+}
@@ -0,0 +1,587 @@
+// Skip list.
+//
+// A sorted linked list takes O(n) to search because we have to walk it one Node
+// at a time. A skip list speeds that up by giving each Node a random number of
+// extra `next` links, one per level. Every Node is on level 0, about half of
+// them are also on level 1, a quarter on level 2, and so on. A search starts on
+// the highest level, where it can skip over many Nodes at once, and drops down
+// a level whenever the next Node would overshoot. On average that visits
+// O(log n) Nodes.
+//
+// Like arena::ArenaList the Nodes live in a Vec and link to each other by
+// index, which keeps this free of unsafe code. Slot 0 holds a head Node that
+// has no entry and a link on every level.
+
+use crate::rng::Rng;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+
+/// The most levels a Node can be on, enough for far more entries than fit in
+/// memory.
+const MAX_LEVEL: usize = 32;
+
+/// The head Node is always in slot 0.
+const HEAD: usize = 0;
+
+/// For each level, the last Node before the place we searched for.
+type Path = [usize; MAX_LEVEL];
+
+/// Ordered map with O(log n) expected lookups, inserts and removals.
+pub struct SkipList<K, V> {
+    nodes: Vec<Node<K, V>>,
+    // Slots of removed Nodes, reused by the next insert.
+    free: Vec<usize>,
+    // The number of levels in use.
+    level: usize,
+    len: usize,
+    rng: Rng,
+}
+
+struct Node<K, V> {
+    // None for the head and for free slots.
+    entry: Option<(K, V)>,
+    next: Vec<Option<usize>>,
+}
+
+impl<K: Ord, V> SkipList<K, V> {
+    /// Creates an empty map.
+    ///
+    /// The levels of the Nodes are chosen with a randomly seeded generator,
+    /// use `with_seed` to make them reproducible.
+    pub fn new() -> Self {
+        use std::hash::{BuildHasher, Hasher};
+        let seed = std::collections::hash_map::RandomState::new()
+            .build_hasher()
+            .finish();
+        SkipList::with_seed(seed)
+    }
+
+    /// Creates an empty map that chooses the levels of its Nodes from the
+    /// random sequence for `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        SkipList {
+            nodes: vec![Node {
+                entry: None,
+                next: vec![None; MAX_LEVEL],
+            }],
+            free: Vec::new(),
+            level: 1,
+            len: 0,
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true iff the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the value for `key`.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.find(&self.path_to(key), key)?;
+        Some(&self.slot(index).1)
+    }
+
+    /// Returns the value for `key`.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.find(&self.path_to(key), key)?;
+        Some(&mut self.slot_mut(index).1)
+    }
+
+    /// Returns true iff the map has an entry for `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Sets the value for `key`, and returns the previous value if there was
+    /// one.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Removes the entry for `key` and returns its value.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let path = self.path_to(key);
+        let index = self.find(&path, key)?;
+        Some(self.remove_at(&path, index).1)
+    }
+
+    /// Returns the entry for `key`, for inserting or updating it in place.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        let path = self.path_to(&key);
+        match self.find(&path, &key) {
+            Some(index) => Entry::Occupied(OccupiedEntry {
+                list: self,
+                path,
+                index,
+            }),
+            None => Entry::Vacant(VacantEntry {
+                list: self,
+                path,
+                key,
+            }),
+        }
+    }
+
+    /// Returns an iterator over the entries in key order.
+    pub fn iter(&self) -> Range<'_, K, V> {
+        self.range(..)
+    }
+
+    /// Returns an iterator over the entries with keys in `range`, in key order.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Range<'_, K, V> {
+        let start = self.seek(|key| match range.start_bound() {
+            Bound::Included(start) => key < start,
+            Bound::Excluded(start) => key <= start,
+            Bound::Unbounded => false,
+        })[0];
+        let end = self.seek(|key| match range.end_bound() {
+            Bound::Included(end) => key <= end,
+            Bound::Excluded(end) => key < end,
+            Bound::Unbounded => true,
+        })[0];
+        // If the range is empty, or backwards, its first Node is after its last.
+        let first = self.nodes[start].next[0]
+            .filter(|&first| end != HEAD && self.slot(first).0 <= self.slot(end).0);
+        Range {
+            list: self,
+            current: first,
+            last: end,
+        }
+    }
+
+    /// Returns the path to the first Node for which `before` returns false.
+    ///
+    /// `before` must return true for a prefix of the keys in order.
+    fn seek<F: Fn(&K) -> bool>(&self, before: F) -> Path {
+        let mut path = [HEAD; MAX_LEVEL];
+        let mut current = HEAD;
+        for level in (0..self.level).rev() {
+            while let Some(next) = self.nodes[current].next[level] {
+                if !before(&self.slot(next).0) {
+                    break;
+                }
+                current = next;
+            }
+            path[level] = current;
+        }
+        path
+    }
+
+    fn path_to<Q>(&self, key: &Q) -> Path
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.seek(|k| k.borrow() < key)
+    }
+
+    /// Returns the Node after `path` if it has `key`.
+    fn find<Q>(&self, path: &Path, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.nodes[path[0]].next[0]?;
+        match self.slot(index).0.borrow().cmp(key) {
+            Ordering::Equal => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Picks a random level, each one half as likely as the one below.
+    fn random_level(&mut self) -> usize {
+        (self.rng.next_u64().trailing_ones() as usize + 1).min(MAX_LEVEL)
+    }
+
+    /// Links a new Node after `path` and returns its slot.
+    fn insert_at(&mut self, path: &Path, key: K, value: V) -> usize {
+        let height = self.random_level();
+        let mut path = *path;
+        // Levels that weren't in use yet start from the head.
+        for slot in path.iter_mut().take(height).skip(self.level) {
+            *slot = HEAD;
+        }
+        self.level = self.level.max(height);
+
+        let next = (0..height).map(|level| self.nodes[path[level]].next[level]);
+        let node = Node {
+            entry: Some((key, value)),
+            next: next.collect(),
+        };
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        for (level, &before) in path.iter().enumerate().take(height) {
+            self.nodes[before].next[level] = Some(index);
+        }
+        self.len += 1;
+        index
+    }
+
+    /// Unlinks the Node in slot `index`, which comes right after `path`.
+    fn remove_at(&mut self, path: &Path, index: usize) -> (K, V) {
+        let node = std::mem::replace(
+            &mut self.nodes[index],
+            Node {
+                entry: None,
+                next: Vec::new(),
+            },
+        );
+        for (level, next) in node.next.into_iter().enumerate() {
+            self.nodes[path[level]].next[level] = next;
+        }
+        while self.level > 1 && self.nodes[HEAD].next[self.level - 1].is_none() {
+            self.level -= 1;
+        }
+        self.free.push(index);
+        self.len -= 1;
+        node.entry.expect("removed Node has an entry")
+    }
+}
+
+impl<K, V> SkipList<K, V> {
+    fn slot(&self, index: usize) -> &(K, V) {
+        self.nodes[index]
+            .entry
+            .as_ref()
+            .expect("linked Node has an entry")
+    }
+
+    fn slot_mut(&mut self, index: usize) -> &mut (K, V) {
+        self.nodes[index]
+            .entry
+            .as_mut()
+            .expect("linked Node has an entry")
+    }
+}
+
+impl<K: Ord, V> Default for SkipList<K, V> {
+    fn default() -> Self {
+        SkipList::new()
+    }
+}
+
+impl<K: Ord, V> std::iter::FromIterator<(K, V)> for SkipList<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut list = SkipList::new();
+        for (key, value) in iter {
+            list.insert(key, value);
+        }
+        list
+    }
+}
+
+impl<K: Ord + std::fmt::Debug, V: std::fmt::Debug> std::fmt::Debug for SkipList<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K: Ord, V> IntoIterator for &'a SkipList<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Range<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over a range of the entries of a SkipList.
+pub struct Range<'a, K, V> {
+    list: &'a SkipList<K, V>,
+    current: Option<usize>,
+    // The last Node in the range.
+    last: usize,
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.current?;
+        self.current = match index == self.last {
+            true => None,
+            false => self.list.nodes[index].next[0],
+        };
+        let (key, value) = self.list.slot(index);
+        Some((key, value))
+    }
+}
+
+impl<'a, K, V> std::iter::FusedIterator for Range<'a, K, V> {}
+
+/// A view into a single entry of a SkipList, see `SkipList::entry`.
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+/// An entry that has a value.
+pub struct OccupiedEntry<'a, K, V> {
+    list: &'a mut SkipList<K, V>,
+    path: Path,
+    index: usize,
+}
+
+/// An entry that has no value yet.
+pub struct VacantEntry<'a, K, V> {
+    list: &'a mut SkipList<K, V>,
+    path: Path,
+    key: K,
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Returns the value, inserting `default` first if there isn't one.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Returns the value, inserting the result of `default` first if there
+    /// isn't one.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Returns the value, inserting `V::default()` first if there isn't one.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `f` on the value if there is one.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.list.slot(self.index).0
+    }
+
+    pub fn get(&self) -> &V {
+        &self.list.slot(self.index).1
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.list.slot_mut(self.index).1
+    }
+
+    /// Returns the value, borrowed for as long as the map was.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.list.slot_mut(self.index).1
+    }
+
+    /// Replaces the value and returns the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry and returns its value.
+    pub fn remove(self) -> V {
+        self.list.remove_at(&self.path, self.index).1
+    }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Inserts `value` for the key and returns it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let index = self.list.insert_at(&self.path, self.key, value);
+        &mut self.list.slot_mut(index).1
+    }
+}
+
+#[test]
+fn test_insert_get_remove() {
+    let mut list = SkipList::with_seed(1);
+    assert!(list.is_empty());
+    assert_eq!(list.insert("b", 2), None);
+    assert_eq!(list.insert("a", 1), None);
+    assert_eq!(list.insert("c", 3), None);
+    assert_eq!(list.insert("b", 20), Some(2));
+    assert_eq!(list.len(), 3);
+
+    assert_eq!(list.get("a"), Some(&1));
+    assert_eq!(list.get("b"), Some(&20));
+    assert_eq!(list.get("d"), None);
+    *list.get_mut("c").unwrap() += 1;
+    assert!(list.contains_key("c"));
+    assert_eq!(format!("{:?}", list), r#"{"a": 1, "b": 20, "c": 4}"#);
+
+    assert_eq!(list.remove("b"), Some(20));
+    assert_eq!(list.remove("b"), None);
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.insert("b", 5), None);
+    assert_eq!(format!("{:?}", list), r#"{"a": 1, "b": 5, "c": 4}"#);
+}
+
+#[test]
+fn test_borrowed_keys() {
+    let mut list = SkipList::with_seed(2);
+    list.insert("one".to_string(), 1);
+    list.insert("two".to_string(), 2);
+    assert_eq!(list.get("two"), Some(&2));
+    assert_eq!(list.remove("one"), Some(1));
+    assert!(!list.contains_key("one"));
+}
+
+#[test]
+fn test_range() {
+    let list: SkipList<_, _> = (0..10).map(|n| (n * 10, n)).collect();
+    let keys = |range: Range<i32, i32>| range.map(|(&k, _)| k).collect::<Vec<_>>();
+    assert_eq!(
+        keys(list.iter()),
+        (0..10).map(|n| n * 10).collect::<Vec<_>>()
+    );
+    assert_eq!(keys(list.range(20..50)), vec![20, 30, 40]);
+    assert_eq!(keys(list.range(15..=50)), vec![20, 30, 40, 50]);
+    assert_eq!(keys(list.range(..15)), vec![0, 10]);
+    assert_eq!(keys(list.range(85..)), vec![90]);
+    assert_eq!(
+        keys(list.range((Bound::Excluded(20), Bound::Excluded(50)))),
+        vec![30, 40]
+    );
+    assert_eq!(keys(list.range(100..)), Vec::<i32>::new());
+    assert_eq!(keys(list.range(..0)), Vec::<i32>::new());
+    assert_eq!(keys(list.range(31..39)), Vec::<i32>::new());
+    assert_eq!(keys(list.range(40..40)), Vec::<i32>::new());
+    assert_eq!(
+        keys(SkipList::<i32, i32>::with_seed(0).iter()),
+        Vec::<i32>::new()
+    );
+}
+
+#[test]
+fn test_entry() {
+    let mut counts = SkipList::with_seed(3);
+    for word in "a b a c b a".split(' ') {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    assert_eq!(format!("{:?}", counts), r#"{"a": 3, "b": 2, "c": 1}"#);
+
+    counts.entry("a").and_modify(|n| *n *= 10).or_default();
+    counts.entry("d").and_modify(|n| *n *= 10).or_default();
+    assert_eq!(counts.get("a"), Some(&30));
+    assert_eq!(counts.get("d"), Some(&0));
+    assert_eq!(counts.entry("e").key(), &"e");
+
+    match counts.entry("b") {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.key(), &"b");
+            assert_eq!(entry.insert(7), 2);
+            assert_eq!(entry.get(), &7);
+            assert_eq!(entry.remove(), 7);
+        }
+        Entry::Vacant(_) => unreachable!(),
+    }
+    assert_eq!(format!("{:?}", counts), r#"{"a": 30, "c": 1, "d": 0}"#);
+}
+
+#[test]
+fn test_large() {
+    let mut list = SkipList::with_seed(4);
+    for i in (0..100_000u32).rev() {
+        list.insert(i.wrapping_mul(2_654_435_761), i);
+    }
+    assert_eq!(list.len(), 100_000);
+    assert!(list.level > 10 && list.level < MAX_LEVEL);
+    for i in 0..100_000u32 {
+        assert_eq!(list.get(&i.wrapping_mul(2_654_435_761)), Some(&i));
+    }
+    let keys: Vec<_> = list.iter().map(|(&k, _)| k).collect();
+    assert!(keys.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn test_matches_btree_map() {
+    use std::collections::BTreeMap;
+
+    // Random operations on a small set of keys, so that inserts often replace
+    // and removes often hit.
+    for seed in 0..20 {
+        let mut rng = Rng::new(seed);
+        let mut list = SkipList::with_seed(seed);
+        let mut map = BTreeMap::new();
+        for step in 0..2_000 {
+            let key = rng.below(64) as u8;
+            match rng.below(6) {
+                0 | 1 => assert_eq!(list.insert(key, step), map.insert(key, step)),
+                2 => assert_eq!(list.remove(&key), map.remove(&key)),
+                3 => assert_eq!(list.get(&key), map.get(&key)),
+                4 => {
+                    let a = list.entry(key).and_modify(|v| *v += 1).or_insert(step);
+                    let b = map.entry(key).and_modify(|v| *v += 1).or_insert(step);
+                    assert_eq!(a, b);
+                }
+                _ => {
+                    let end = rng.below(70) as u8;
+                    let got: Vec<_> = list.range(key..end).collect();
+                    let want: Vec<_> = if key <= end {
+                        map.range(key..end).collect()
+                    } else {
+                        Vec::new()
+                    };
+                    assert_eq!(got, want, "seed {} step {}", seed, step);
+                }
+            }
+            assert_eq!(list.len(), map.len());
+        }
+        assert!(list.iter().eq(map.iter()), "seed {}", seed);
+    }
+}
@@ -31,24 +31,118 @@
 /// Node is a value in the list, as well as the next "pointer". This is like the
 /// cons cell in lisp.
 ///
+/// The List also keeps count of how many Nodes there are, so that we don't
+/// need to walk the whole list to find its length, and a pointer to the last
+/// Node so that we can add elements to the back without walking the list.
+///
 /// Take a moment to be sure you understand the type definitions, they will be
 /// crucial to implementing the functions.
 pub struct List<T> {
-    head: Option<Box<Node<T>>>,
+    head: Link<T>,
+    tail: Tail<T>,
+    len: usize,
 }
 
+/// A nullable pointer to a Node.
+type Link<T> = Option<NodeBox<T>>;
+
+/// A pointer to the last Node of a List, or None if the List is empty.
+///
+/// The Nodes are all owned by the Links, this is only a second way to reach
+/// the last one. Every function that adds or removes Nodes needs to keep it
+/// pointing at the last Node.
+//
+// The tail is always a copy of the pointer in the NodeBox that owns the last
+// Node, never a pointer made from a reference to the Node. References made
+// from the NodeBox while we walk the list don't outlive the walk, so once we
+// are back in a `&mut List` method nothing else uses the last Node and the
+// tail can read or write it.
+type Tail<T> = Option<std::ptr::NonNull<Node<T>>>;
+
 struct Node<T> {
     val: T,
-    next: List<T>,
+    next: Link<T>,
+}
+
+/// An owning pointer to a Node, which works just like a `Box<Node<T>>`.
+//
+// A Box promises that it is the only pointer to its Node, so moving or
+// borrowing a Box would invalidate the tail pointer to the same Node (Miri
+// reports this as undefined behaviour). A NonNull makes no such promise, so
+// the NodeBox and the tail can share the Node as long as only one of them is
+// used at a time.
+struct NodeBox<T>(std::ptr::NonNull<Node<T>>);
+
+impl<T> NodeBox<T> {
+    fn new(node: Node<T>) -> Self {
+        NodeBox(std::ptr::NonNull::from(Box::leak(Box::new(node))))
+    }
+
+    /// Frees the NodeBox and returns the Node it owned.
+    fn into_inner(this: Self) -> Node<T> {
+        let this = std::mem::ManuallyDrop::new(this);
+        // SAFETY: the pointer came from Box::leak in new, and the ManuallyDrop
+        // makes sure that this is the only time it is turned back into a Box.
+        *unsafe { Box::from_raw(this.0.as_ptr()) }
+    }
+
+    /// Returns the pointer to the Node, for use as a Tail.
+    fn as_ptr(this: &Self) -> std::ptr::NonNull<Node<T>> {
+        this.0
+    }
+}
+
+impl<T> std::ops::Deref for NodeBox<T> {
+    type Target = Node<T>;
+
+    fn deref(&self) -> &Node<T> {
+        // SAFETY: the NodeBox owns the Node, and the borrow of the NodeBox
+        // stops anyone from changing it through the NodeBox or the List.
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl<T> std::ops::DerefMut for NodeBox<T> {
+    fn deref_mut(&mut self) -> &mut Node<T> {
+        // SAFETY: as in deref, and the tail isn't used while the List is
+        // mutably borrowed through this NodeBox.
+        unsafe { self.0.as_mut() }
+    }
+}
+
+/// Drops the Node and the rest of the Nodes after it one at a time.
+//
+// For the same reason as in List::drop: the Nodes that a NodeBox owns can be a
+// long chain, for example a run that sort_by is merging when the comparison
+// panics, and dropping them recursively could overflow the stack.
+impl<T> Drop for NodeBox<T> {
+    fn drop(&mut self) {
+        let mut next = self.next.take();
+        while let Some(mut node) = next {
+            next = node.next.take();
+        }
+        // SAFETY: the pointer came from Box::leak in new and is only freed
+        // here or in into_inner.
+        drop(unsafe { Box::from_raw(self.0.as_ptr()) });
+    }
 }
 
+// Like a Box, a NodeBox can be sent to or shared with another thread whenever
+// the Node can.
+unsafe impl<T: Send> Send for NodeBox<T> {}
+unsafe impl<T: Sync> Sync for NodeBox<T> {}
+
 impl<T> List<T> {
     /// Creates an empty list.
     ///
     /// This "static" function constructs a new list with no elements.
     pub fn empty() -> Self {
         // unimplemented!("codelab::List::empty()");
-        List { head: None }
+        List {
+            head: None,
+            tail: None,
+            len: 0,
+        }
     }
 
     /// Returns true iff the list is empty.
@@ -57,6 +151,11 @@ impl<T> List<T> {
         self.head.is_none()
     }
 
+    /// Returns the number of elements in the list.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
     /// Adds a new element to the front of the list.
     pub fn push(&mut self, element: T) {
         // Tip: Rust doesn't allow invalid values, so it might be difficult to move
@@ -72,12 +171,14 @@ impl<T> List<T> {
 
         let new_head = Node {
             val: element,
-            next: List {
-                head: previous_head,
-            },
+            next: previous_head,
         };
 
-        self.head = Some(Box::new(new_head));
+        self.head = Some(NodeBox::new(new_head));
+        if self.tail.is_none() {
+            self.tail = self.head.as_ref().map(NodeBox::as_ptr);
+        }
+        self.len += 1;
     }
 
     /// Removes the first element from the list and return it.
@@ -94,8 +195,12 @@ impl<T> List<T> {
         let previous_head = self.head.take();
         match previous_head {
             Some(box_tail) => {
-                let tail = *box_tail;
-                self.head = tail.next.head;
+                let tail = NodeBox::into_inner(box_tail);
+                self.head = tail.next;
+                if self.head.is_none() {
+                    self.tail = None;
+                }
+                self.len -= 1;
                 Some(tail.val)
             }
             None => None,
@@ -103,6 +208,51 @@ impl<T> List<T> {
     }
 }
 
+/// Drops the list one Node at a time.
+//
+// The compiler generated drop would drop the head Node, which drops the next
+// Link, which drops the next Node and so on. Every one of those calls needs
+// some stack so a long enough list would overflow the stack. Instead we unlink
+// each Node before dropping it so that dropping a Node never recurses.
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        self.tail = None;
+        let mut current = self.head.take();
+        while let Some(mut node) = current {
+            current = node.next.take();
+        }
+    }
+}
+
+// The tail pointer stops the compiler from working out that a List can be sent
+// to or shared with another thread, but it is only ever used while we have
+// access to the List so the same rules as for the Nodes apply.
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Sync> Sync for List<T> {}
+
+#[test]
+fn test_drop_long_list() {
+    let mut list = List::empty();
+    for i in 0..10_000_000 {
+        list.push(i);
+    }
+    drop(list);
+}
+
+#[test]
+fn test_len() {
+    let mut list = List::empty();
+    assert_eq!(list.len(), 0);
+    list.push(1);
+    list.push(2);
+    assert_eq!(list.len(), 2);
+    list.pop();
+    assert_eq!(list.len(), 1);
+    list.pop();
+    list.pop();
+    assert_eq!(list.len(), 0);
+}
+
 #[test]
 fn test_01_empty() {
     // Just having a constructor isn't very useful so we will just ensure it
@@ -115,16 +265,27 @@ fn test_01_empty() {
 fn test_02_is_empty() {
     // Note: We don't have push yet, so we will implement it ourselves.
 
-    let list = List::empty();
+    let mut list = List::empty();
     assert!(list.is_empty());
 
-    let list = List {
-        head: Some(Box::new(Node { val: 1, next: list })),
+    // is_empty() doesn't look at the tail so we don't bother setting it.
+    let mut list = List {
+        head: Some(NodeBox::new(Node {
+            val: 1,
+            next: list.head.take(),
+        })),
+        tail: None,
+        len: 1,
     };
     assert!(!list.is_empty());
 
     let list = List {
-        head: Some(Box::new(Node { val: 2, next: list })),
+        head: Some(NodeBox::new(Node {
+            val: 2,
+            next: list.head.take(),
+        })),
+        tail: None,
+        len: 2,
     };
     assert!(!list.is_empty());
 }
@@ -201,11 +362,24 @@ impl<T> Iterator for DrainingIterator<T> {
         // unimplemented!("codelab::DrainingIterator::next(&mut self)");
         self.0.pop()
     }
+
+    /// Returns the exact number of items left, since the list knows its length.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
 }
 
+impl<T> ExactSizeIterator for DrainingIterator<T> {}
+
+impl<T> std::iter::FusedIterator for DrainingIterator<T> {}
+
 // Unless the signatures are changed our list must work with non-copyable data,
 // but just to be sure I made this part of the tests :)
 #[derive(Debug, PartialEq)]
+#[cfg_attr(
+    all(test, feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
 struct NonCopyable(u32);
 
 #[test]
@@ -268,8 +442,12 @@ fn test_06_into_draining_iter() {
 // list. To do this we also need to ensure that the Iterator doesn't live
 // longer then the list we are iterating over, otherwise we would have dangling
 // references.
+//
+// We also keep track of how many elements are left so that we can report an
+// exact size_hint.
 pub struct ReferenceIter<'a, T: 'a> {
-    current: &'a List<T>,
+    current: &'a Link<T>,
+    len: usize,
 }
 
 /// Reference iterator for reference to list list.
@@ -282,16 +460,25 @@ impl<'a, T> Iterator for ReferenceIter<'a, T> {
 
     fn next(&mut self) -> Option<Self::Item> {
         // unimplemented!("Iterator::<&List<T>>::next(&mut self)");
-        match &self.current.head {
+        match self.current {
             Some(tail) => {
                 self.current = &tail.next;
+                self.len -= 1;
                 Some(&tail.val)
             }
             None => None,
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
+impl<'a, T> ExactSizeIterator for ReferenceIter<'a, T> {}
+
+impl<'a, T> std::iter::FusedIterator for ReferenceIter<'a, T> {}
+
 #[test]
 fn test_07_reference_iter_next() {
     let mut list = List::empty();
@@ -299,7 +486,10 @@ fn test_07_reference_iter_next() {
     list.push(NonCopyable(2));
     list.push(NonCopyable(3));
 
-    let mut iter = ReferenceIter { current: &list };
+    let mut iter = ReferenceIter {
+        current: &list.head,
+        len: list.len,
+    };
     assert_eq!(iter.next(), Some(&NonCopyable(3)));
     assert_eq!(iter.next(), Some(&NonCopyable(2)));
     assert_eq!(iter.next(), Some(&NonCopyable(1)));
@@ -319,7 +509,10 @@ impl<'a, T> IntoIterator for &'a List<T> {
         // Hint: Remember that `self: &List<T>`
         // Hint: Look at the previous IntoIterator impl that I provided for you.
         // unimplemented!("IntoIterator::<&List<T>>::into_iter(self)");
-        ReferenceIter { current: self }
+        ReferenceIter {
+            current: &self.head,
+            len: self.len,
+        }
     }
 }
 
@@ -340,190 +533,1813 @@ fn test_08_into_reference_iter() {
     assert_eq!(results, vec![1, 2, 3, 2, 4, 6]);
 }
 
-#[derive(Debug, PartialEq)]
-struct Score {
-    home_points: u32,
-    away_points: u32,
+/// Mutable reference iterator for a mutable reference to list.
+///
+/// This allows someone to walk their List and modify each element in place
+/// without draining it and building a new list.
+//
+// Unlike ReferenceIter we can't keep a `&'a mut List<T>` and move it forward,
+// since we would need to hand out the `&'a mut T` while still holding a
+// mutable reference to the node that contains it. Instead we keep the next
+// Node itself and split it into the value (which we return) and the rest of
+// the list (which we keep).
+pub struct IterMut<'a, T: 'a> {
+    current: Option<&'a mut Node<T>>,
+    len: usize,
 }
 
-#[derive(Debug, PartialEq)]
-enum Team {
-    Draw,
-    Home,
-    Away,
+impl<'a, T> Iterator for IterMut<'a, T> {
+    /// The type of element we will yield.
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.current.take() {
+            Some(node) => {
+                self.current = node.next.as_deref_mut();
+                self.len -= 1;
+                Some(&mut node.val)
+            }
+            None => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
-/// Returns the total score for the home and away teams across games.
-///
-/// Given a List<Score> representing the scores for a sequence of games, produce
-/// a Score representing the total of those scores.
-fn total_score(results: &List<Score>) -> Score {
-    // Hint: You can use a for loop to iterate over the results.
-    // https://doc.rust-lang.org/1.1.0/book/for-loops.html
-    //
-    // Hint: Or you could call `results.into_iter()` and use any of the methods
-    // provided by the iterator trait.
-    // https://doc.rust-lang.org/std/iter/trait.Iterator.html
-    // unimplemented!("codelab::total_score()");
-    results.into_iter().fold(
-        Score {
-            home_points: 0,
-            away_points: 0,
-        },
-        |Score {
-             home_points,
-             away_points,
-         },
-         result| Score {
-            home_points: home_points + result.home_points,
-            away_points: away_points + result.away_points,
-        },
-    )
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> std::iter::FusedIterator for IterMut<'a, T> {}
+
+impl<T> List<T> {
+    /// Returns an iterator that allows modifying each element.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            current: self.head.as_deref_mut(),
+            len: self.len,
+        }
+    }
 }
 
 #[test]
-fn test_09_total_score() {
-    let mut scores = List::empty();
-    assert_eq!(
-        total_score(&scores),
-        Score {
-            home_points: 0,
-            away_points: 0
-        }
-    );
+fn test_iter_mut_next() {
+    let mut list = List::empty();
+    list.push(NonCopyable(1));
+    list.push(NonCopyable(2));
+    list.push(NonCopyable(3));
 
-    scores.push(Score {
-        home_points: 0,
-        away_points: 0,
-    });
-    assert_eq!(
-        total_score(&scores),
-        Score {
-            home_points: 0,
-            away_points: 0
-        }
-    );
+    let mut iter = list.iter_mut();
+    assert_eq!(iter.next(), Some(&mut NonCopyable(3)));
+    assert_eq!(iter.next(), Some(&mut NonCopyable(2)));
+    assert_eq!(iter.next(), Some(&mut NonCopyable(1)));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next(), None);
+}
 
-    scores.push(Score {
-        home_points: 3,
-        away_points: 0,
-    });
-    assert_eq!(
-        total_score(&scores),
-        Score {
-            home_points: 3,
-            away_points: 0
-        }
-    );
+#[test]
+#[cfg(fail)]
+fn example_iter_mut_aliasing() {
+    let mut list = List::empty();
+    list.push(NonCopyable(1));
 
-    scores.push(Score {
-        home_points: 0,
-        away_points: 3,
-    });
-    assert_eq!(
-        total_score(&scores),
-        Score {
-            home_points: 3,
-            away_points: 3
-        }
-    );
+    let mut iter = list.iter_mut();
+    let first = iter.next().unwrap();
+    list.push(NonCopyable(2));
+    first.0 = 3;
+}
 
-    scores.push(Score {
-        home_points: 2,
-        away_points: 1,
-    });
+/// Into IterMut for a mutable reference to a List.
+impl<'a, T> IntoIterator for &'a mut List<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    /// Returns an IterMut for this list.
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[test]
+fn test_into_iter_mut() {
+    let mut list = List::empty();
+    list.push(NonCopyable(3));
+    list.push(NonCopyable(2));
+    list.push(NonCopyable(1));
+
+    for item in &mut list {
+        item.0 *= 10;
+    }
+    for item in &mut list {
+        item.0 += 1;
+    }
+
+    let results: Vec<_> = list.into_iter().collect();
     assert_eq!(
-        total_score(&scores),
-        Score {
-            home_points: 5,
-            away_points: 4
-        }
+        results,
+        vec![NonCopyable(11), NonCopyable(21), NonCopyable(31)]
     );
 }
 
-/// Returns the Team with the highest score over all the games in the list.
-fn highest_total_score(results: &List<Score>) -> Team {
-    // Hint: total_score() might be useful :)
-    // unimplemented!("codelab::highest_total_score()");
-    let Score {
-        home_points,
-        away_points,
-    } = total_score(results);
+/// A cursor over a List that can edit the list at its position.
+///
+/// The cursor always points at an element of the list, or "past the end" of
+/// it once it has walked off the last element. All of the editing operations
+/// are O(1).
+//
+// The position of the cursor is the Link that points at the current element.
+// Replacing what that Link points to is all we need to insert or remove
+// elements at the cursor. We also borrow the length and tail of the List so
+// that we can keep them up to date, which is why we remember the Node before
+// the current one: it becomes the tail if we remove the last element.
+//
+// The Option is only ever None while we are moving the cursor, it allows us to
+// take the `&'a mut Link<T>` out of the cursor and replace it with one that
+// points further down the list.
+pub struct CursorMut<'a, T: 'a> {
+    current: Option<&'a mut Link<T>>,
+    prev: Tail<T>,
+    tail: &'a mut Tail<T>,
+    len: &'a mut usize,
+    index: usize,
+}
 
-    match home_points.cmp(&away_points) {
-        std::cmp::Ordering::Greater => Team::Home,
-        std::cmp::Ordering::Equal => Team::Draw,
-        std::cmp::Ordering::Less => Team::Away,
+impl<T> List<T> {
+    /// Returns a cursor pointing at the first element of the list.
+    ///
+    /// If the list is empty the cursor is already past the end.
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: Some(&mut self.head),
+            prev: None,
+            tail: &mut self.tail,
+            len: &mut self.len,
+            index: 0,
+        }
     }
 }
 
-#[test]
-fn test_10_highest_total_score() {
-    let mut scores = List::empty();
-    assert_eq!(highest_total_score(&scores), Team::Draw);
+impl<'a, T> CursorMut<'a, T> {
+    fn take(&mut self) -> &'a mut Link<T> {
+        self.current
+            .take()
+            .expect("CursorMut always has a position")
+    }
 
-    scores.push(Score {
-        home_points: 0,
-        away_points: 0,
-    });
-    assert_eq!(highest_total_score(&scores), Team::Draw);
+    fn link(&mut self) -> &mut Link<T> {
+        self.current
+            .as_deref_mut()
+            .expect("CursorMut always has a position")
+    }
 
-    scores.push(Score {
-        home_points: 3,
-        away_points: 0,
-    });
-    assert_eq!(highest_total_score(&scores), Team::Home);
+    /// Returns the index of the current element, or None if the cursor is past
+    /// the end of the list.
+    pub fn index(&self) -> Option<usize> {
+        if self.index < *self.len {
+            Some(self.index)
+        } else {
+            None
+        }
+    }
 
-    scores.push(Score {
-        home_points: 0,
-        away_points: 3,
-    });
-    assert_eq!(highest_total_score(&scores), Team::Draw);
+    /// Returns the current element, or None if the cursor is past the end of
+    /// the list.
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.link().as_mut().map(|node| &mut node.val)
+    }
 
-    scores.push(Score {
-        home_points: 2,
-        away_points: 1,
-    });
-    assert_eq!(highest_total_score(&scores), Team::Home);
-}
+    /// Returns the element after the current one.
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        match self.link() {
+            Some(node) => node.next.as_mut().map(|node| &mut node.val),
+            None => None,
+        }
+    }
 
-/// Returns the games where the home team wins!
-///
-/// Given a List<Score> returns one with only the winning games.
-fn games_worth_watching(results: List<Score>) -> List<Score> {
-    // unimplemented!("codelab::games_worth_watching()");
-    let mut good = List::empty();
-    for r in results {
-        if r.home_points > r.away_points {
-            good.push(r);
+    /// Moves the cursor to the next element.
+    ///
+    /// Once the cursor is past the end of the list this does nothing.
+    pub fn move_next(&mut self) {
+        let current = self.take();
+        if current.is_none() {
+            self.current = Some(current);
+            return;
         }
+        let node = current.as_mut().unwrap();
+        self.prev = Some(NodeBox::as_ptr(node));
+        self.current = Some(&mut node.next);
+        self.index += 1;
     }
-    good
-}
 
-#[test]
-fn test_11_games_worth_watching() {
-    let mut scores = List::empty();
-    scores.push(Score {
-        home_points: 0,
-        away_points: 0,
-    });
-    scores.push(Score {
-        home_points: 0,
-        away_points: 3,
-    });
-    let mut good = games_worth_watching(scores);
-    assert_eq!(good.pop(), None);
+    /// Inserts a new element before the current one.
+    ///
+    /// The cursor keeps pointing at the same element. If the cursor is past
+    /// the end of the list this appends the element.
+    pub fn insert_before(&mut self, element: T) {
+        let link = self.link();
+        let at_end = link.is_none();
+        let node = insert(link, element);
+        if at_end {
+            *self.tail = Some(node);
+        }
+        *self.len += 1;
+        self.move_next();
+    }
 
-    let mut scores = List::empty();
-    scores.push(Score {
-        home_points: 0,
-        away_points: 0,
-    });
-    scores.push(Score {
-        home_points: 5,
-        away_points: 3,
-    });
+    /// Inserts a new element after the current one.
+    ///
+    /// If the cursor is past the end of the list this appends the element, just
+    /// like `insert_before`.
+    pub fn insert_after(&mut self, element: T) {
+        match self.link() {
+            Some(node) => {
+                let at_end = node.next.is_none();
+                let node = insert(&mut node.next, element);
+                if at_end {
+                    *self.tail = Some(node);
+                }
+                *self.len += 1;
+            }
+            None => self.insert_before(element),
+        }
+    }
+
+    /// Removes the current element from the list and returns it.
+    ///
+    /// The cursor moves on to the element that followed the removed one.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let link = self.link();
+        match link.take() {
+            Some(node) => {
+                let node = NodeBox::into_inner(node);
+                *link = node.next;
+                if link.is_none() {
+                    *self.tail = self.prev;
+                }
+                *self.len -= 1;
+                Some(node.val)
+            }
+            None => None,
+        }
+    }
+
+    /// Removes all of the elements after the current one and returns them as
+    /// a new list.
+    ///
+    /// If the cursor is past the end of the list the returned list is empty.
+    pub fn split_after(&mut self) -> List<T> {
+        let index = self.index;
+        match self.link() {
+            Some(node) if node.next.is_some() => {
+                let current = NodeBox::as_ptr(node);
+                let split = List {
+                    head: node.next.take(),
+                    tail: self.tail.replace(current),
+                    len: *self.len - index - 1,
+                };
+                *self.len = index + 1;
+                split
+            }
+            _ => List::empty(),
+        }
+    }
+
+    /// Inserts all of the elements of `list` after the current element.
+    ///
+    /// If the cursor is past the end of the list the elements are appended and
+    /// the cursor stays past the end.
+    pub fn splice_after(&mut self, mut list: List<T>) {
+        let list_tail = match list.tail.take() {
+            Some(list_tail) => list_tail,
+            None => return,
+        };
+        let spliced = std::mem::replace(&mut list.len, 0);
+        match self.link() {
+            Some(node) => {
+                let at_end = node.next.is_none();
+                // SAFETY: list_tail points at the last Node of `list`, which
+                // we own and no one else can access.
+                unsafe { (*list_tail.as_ptr()).next = node.next.take() };
+                node.next = list.head.take();
+                if at_end {
+                    *self.tail = Some(list_tail);
+                }
+            }
+            None => {
+                let current = self.take();
+                *current = list.head.take();
+                *self.tail = Some(list_tail);
+                self.prev = Some(list_tail);
+                // SAFETY: list_tail points at the last Node of our list now,
+                // and the cursor has exclusive access to the list for 'a.
+                self.current = Some(unsafe { &mut (*list_tail.as_ptr()).next });
+                self.index += spliced;
+            }
+        }
+        *self.len += spliced;
+    }
+}
+
+/// Inserts a new Node holding `element` at `link` and returns a pointer to it.
+fn insert<T>(link: &mut Link<T>, element: T) -> std::ptr::NonNull<Node<T>> {
+    let next = link.take();
+    NodeBox::as_ptr(link.insert(NodeBox::new(Node { val: element, next })))
+}
+
+#[test]
+fn test_cursor_move_next() {
+    let mut list = List::empty();
+    list.push(NonCopyable(2));
+    list.push(NonCopyable(1));
+
+    let mut cursor = list.cursor_front_mut();
+    assert_eq!(cursor.index(), Some(0));
+    assert_eq!(cursor.current(), Some(&mut NonCopyable(1)));
+    assert_eq!(cursor.peek_next(), Some(&mut NonCopyable(2)));
+
+    cursor.move_next();
+    assert_eq!(cursor.index(), Some(1));
+    assert_eq!(cursor.current(), Some(&mut NonCopyable(2)));
+    assert_eq!(cursor.peek_next(), None);
+    cursor.current().unwrap().0 = 5;
+
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.current(), None);
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+
+    let items: Vec<_> = list.into_iter().collect();
+    assert_eq!(items, vec![NonCopyable(1), NonCopyable(5)]);
+
+    let mut list = List::<NonCopyable>::empty();
+    let mut cursor = list.cursor_front_mut();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), None);
+}
+
+#[test]
+fn test_cursor_insert() {
+    let mut list = List::empty();
+    list.push(3);
+    list.push(1);
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.insert_after(2);
+    assert_eq!(cursor.current(), Some(&mut 1));
+    cursor.insert_before(0);
+    assert_eq!(cursor.current(), Some(&mut 1));
+    assert_eq!(cursor.index(), Some(1));
+
+    cursor.move_next();
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+    cursor.insert_after(4);
+    cursor.insert_before(5);
+    assert_eq!(cursor.index(), None);
+
+    let items: Vec<_> = list.into_iter().collect();
+    assert_eq!(items, vec![0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_cursor_remove_current() {
+    let mut list = List::empty();
+    list.push(NonCopyable(3));
+    list.push(NonCopyable(2));
+    list.push(NonCopyable(1));
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(NonCopyable(2)));
+    assert_eq!(cursor.current(), Some(&mut NonCopyable(3)));
+    assert_eq!(cursor.remove_current(), Some(NonCopyable(3)));
+    assert_eq!(cursor.remove_current(), None);
+    assert_eq!(cursor.index(), None);
+
+    let items: Vec<_> = list.into_iter().collect();
+    assert_eq!(items, vec![NonCopyable(1)]);
+}
+
+#[test]
+fn test_cursor_split_after() {
+    let mut list = List::empty();
+    list.push(4);
+    list.push(3);
+    list.push(2);
+    list.push(1);
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.move_next();
+    let tail = cursor.split_after();
+    assert_eq!(cursor.peek_next(), None);
+    cursor.move_next();
+    assert!(cursor.split_after().is_empty());
+
+    let items: Vec<_> = list.into_iter().collect();
+    assert_eq!(items, vec![1, 2]);
+    let items: Vec<_> = tail.into_iter().collect();
+    assert_eq!(items, vec![3, 4]);
+}
+
+#[test]
+fn test_cursor_splice_after() {
+    let mut list = List::empty();
+    list.push(4);
+    list.push(1);
+
+    let mut middle = List::empty();
+    middle.push(3);
+    middle.push(2);
+
+    let mut end = List::empty();
+    end.push(6);
+    end.push(5);
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.splice_after(middle);
+    assert_eq!(cursor.current(), Some(&mut 1));
+    assert_eq!(cursor.peek_next(), Some(&mut 2));
+    cursor.splice_after(List::empty());
+    assert_eq!(cursor.peek_next(), Some(&mut 2));
+
+    for _ in 0..4 {
+        cursor.move_next();
+    }
+    assert_eq!(cursor.index(), None);
+    cursor.splice_after(end);
+    assert_eq!(cursor.index(), None);
+    cursor.insert_before(7);
+
+    let items: Vec<_> = list.into_iter().collect();
+    assert_eq!(items, vec![1, 2, 3, 4, 5, 6, 7]);
+}
+
+#[test]
+fn test_cursor_splice_after_at_end() {
+    // Splicing at the end jumps the cursor straight to the tail of the spliced
+    // list, so the cost doesn't depend on the length of either list.
+    let chunks = if cfg!(miri) { 10 } else { 1_000 };
+    let mut list = List::empty();
+    let mut cursor = list.cursor_front_mut();
+    for chunk in 0..chunks {
+        let spliced: List<_> = (1..1_000).map(|i| chunk * 1_000 + i).collect();
+        cursor.splice_after(spliced);
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        cursor.insert_before(chunk * 1_000 + 1_000);
+    }
+    assert_eq!(list.len(), chunks * 1_000);
+    assert_eq!(list.peek_back(), Some(&(chunks * 1_000)));
+    assert!(list.into_iter().eq(1..=chunks * 1_000));
+}
+
+#[test]
+fn test_iterator_len() {
+    let mut list = List::empty();
+    list.push(NonCopyable(1));
+    list.push(NonCopyable(2));
+    list.push(NonCopyable(3));
+
+    let mut iter = list.iter_mut();
+    assert_eq!(iter.len(), 3);
+    iter.next();
+    assert_eq!(iter.size_hint(), (2, Some(2)));
+
+    let mut iter = (&list).into_iter();
+    assert_eq!(iter.len(), 3);
+    iter.next();
+    iter.next();
+    assert_eq!(iter.size_hint(), (1, Some(1)));
+    iter.next();
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next(), None);
+
+    let mut iter = list.into_iter();
+    assert_eq!(iter.len(), 3);
+    iter.next();
+    assert_eq!(iter.len(), 2);
+}
+
+#[test]
+fn test_cursor_len() {
+    let mut list = List::empty();
+    list.push(3);
+    list.push(1);
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.insert_after(2);
+    cursor.insert_before(0);
+    cursor.move_next();
+    let mut tail = cursor.split_after();
+    assert_eq!(tail.len(), 1);
+    tail.push(2);
+    cursor.splice_after(tail);
+    cursor.remove_current();
+    assert_eq!(list.len(), 4);
+
+    let mut cursor = list.cursor_front_mut();
+    for _ in 0..4 {
+        cursor.move_next();
+    }
+    assert_eq!(cursor.index(), None);
+    let mut end = List::empty();
+    end.push(4);
+    cursor.splice_after(end);
+    assert_eq!(cursor.split_after().len(), 0);
+    assert_eq!(list.len(), 5);
+
+    let items: Vec<_> = list.into_iter().collect();
+    assert_eq!(items, vec![0, 1, 2, 3, 4]);
+}
+
+impl<T> List<T> {
+    /// Adds a new element to the back of the list.
+    ///
+    /// Together with `pop` this lets the list be used as a FIFO queue.
+    pub fn push_back(&mut self, element: T) {
+        let new_tail = NodeBox::new(Node {
+            val: element,
+            next: None,
+        });
+        let new_tail_ptr = NodeBox::as_ptr(&new_tail);
+        match self.tail {
+            // SAFETY: the tail points at the last Node of this list, and we
+            // have exclusive access to the list.
+            Some(tail) => unsafe { (*tail.as_ptr()).next = Some(new_tail) },
+            None => self.head = Some(new_tail),
+        }
+        self.tail = Some(new_tail_ptr);
+        self.len += 1;
+    }
+
+    /// Returns the last element of the list.
+    pub fn peek_back(&self) -> Option<&T> {
+        // SAFETY: the tail points at the last Node of this list, which lives
+        // as long as the borrow of the list.
+        self.tail.map(|tail| unsafe { &(*tail.as_ptr()).val })
+    }
+
+    /// Moves all of the elements of `other` to the back of this list.
+    ///
+    /// `other` is left empty.
+    pub fn append(&mut self, other: &mut List<T>) {
+        let other_tail = match other.tail.take() {
+            Some(other_tail) => other_tail,
+            None => return,
+        };
+        let other_head = other.head.take();
+        match self.tail {
+            // SAFETY: see push_back.
+            Some(tail) => unsafe { (*tail.as_ptr()).next = other_head },
+            None => self.head = other_head,
+        }
+        self.tail = Some(other_tail);
+        self.len += std::mem::replace(&mut other.len, 0);
+    }
+}
+
+#[test]
+fn test_push_back() {
+    let mut list = List::empty();
+    assert_eq!(list.peek_back(), None);
+
+    list.push_back(NonCopyable(1));
+    assert_eq!(list.peek_back(), Some(&NonCopyable(1)));
+    list.push_back(NonCopyable(2));
+    list.push(NonCopyable(0));
+    list.push_back(NonCopyable(3));
+    assert_eq!(list.peek_back(), Some(&NonCopyable(3)));
+    assert_eq!(list.len(), 4);
+
+    assert_eq!(list.pop(), Some(NonCopyable(0)));
+    assert_eq!(list.pop(), Some(NonCopyable(1)));
+    assert_eq!(list.pop(), Some(NonCopyable(2)));
+    assert_eq!(list.peek_back(), Some(&NonCopyable(3)));
+    assert_eq!(list.pop(), Some(NonCopyable(3)));
+    assert_eq!(list.peek_back(), None);
+    assert_eq!(list.pop(), None);
+
+    list.push(NonCopyable(5));
+    assert_eq!(list.peek_back(), Some(&NonCopyable(5)));
+    list.push_back(NonCopyable(6));
+    assert_eq!(list.peek_back(), Some(&NonCopyable(6)));
+    assert_eq!(list.pop(), Some(NonCopyable(5)));
+    assert_eq!(list.pop(), Some(NonCopyable(6)));
+}
+
+#[test]
+fn test_append() {
+    let mut list = List::empty();
+    let mut other = List::empty();
+    list.append(&mut other);
+    assert!(list.is_empty());
+
+    other.push_back(1);
+    other.push_back(2);
+    list.append(&mut other);
+    assert!(other.is_empty());
+    assert_eq!(other.peek_back(), None);
+    assert_eq!(list.peek_back(), Some(&2));
+
+    other.push_back(3);
+    list.append(&mut other);
+    list.append(&mut List::empty());
+    list.push_back(4);
+    assert_eq!(list.len(), 4);
+    assert_eq!(list.peek_back(), Some(&4));
+
+    other.push_back(5);
+    assert_eq!(other.len(), 1);
+    assert_eq!(other.peek_back(), Some(&5));
+
+    let items: Vec<_> = list.into_iter().collect();
+    assert_eq!(items, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn test_cursor_tail() {
+    let mut list = List::empty();
+    list.push_back(1);
+    list.push_back(2);
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.move_next();
+    cursor.insert_after(3);
+    assert_eq!(cursor.remove_current(), Some(2));
+    assert_eq!(cursor.remove_current(), Some(3));
+    list.push_back(4);
+    assert_eq!(list.peek_back(), Some(&4));
+
+    let mut cursor = list.cursor_front_mut();
+    let mut tail = cursor.split_after();
+    assert_eq!(list.peek_back(), Some(&1));
+    assert_eq!(tail.peek_back(), Some(&4));
+    tail.push_back(5);
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.splice_after(tail);
+    cursor.move_next();
+    cursor.move_next();
+    cursor.move_next();
+    cursor.insert_before(6);
+    let mut end = List::empty();
+    end.push_back(7);
+    cursor.splice_after(end);
+    cursor.insert_before(8);
+    list.push_back(9);
+    assert_eq!(list.peek_back(), Some(&9));
+
+    let mut cursor = list.cursor_front_mut();
+    while cursor.remove_current().is_some() {}
+    assert_eq!(list.peek_back(), None);
+    list.push_back(10);
+    assert_eq!(list.peek_back(), Some(&10));
+    assert_eq!(list.len(), 1);
+}
+
+#[test]
+fn test_queue() {
+    // push_back and pop are both O(1), so this is quick even for a long
+    // queue. Miri is too slow for that though.
+    let len = if cfg!(miri) { 1_000 } else { 1_000_000 };
+    let mut list = List::empty();
+    let mut next = 0;
+    for i in 0..len {
+        list.push_back(i);
+        assert_eq!(list.peek_back(), Some(&i));
+        if i % 2 == 1 {
+            assert_eq!(list.pop(), Some(next));
+            next += 1;
+        }
+    }
+    assert_eq!(list.len(), len / 2);
+    while let Some(i) = list.pop() {
+        assert_eq!(i, next);
+        next += 1;
+    }
+    assert_eq!(next, len);
+    assert_eq!(list.peek_back(), None);
+}
+
+/// Creates a List containing the arguments.
+///
+/// The elements are in the same order as the arguments, so `list![1, 2, 3]`
+/// has 1 at the front.
+#[macro_export]
+macro_rules! list {
+    ($($element:expr),* $(,)?) => {
+        <$crate::solution::List<_> as ::std::iter::FromIterator<_>>::from_iter(
+            ::std::vec![$($element),*],
+        )
+    };
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        List::empty()
+    }
+}
+
+/// Collects an Iterator into a List.
+///
+/// The list keeps the order of the iterator, the first element yielded ends up
+/// at the front of the list.
+impl<T> std::iter::FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::empty();
+        list.extend(iter);
+        list
+    }
+}
+
+/// Appends the elements of an Iterator to the back of a List.
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push_back(element);
+        }
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        self.into_iter().cloned().collect()
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.into_iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.into_iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.into_iter().cmp(other)
+    }
+}
+
+impl<T: std::hash::Hash> std::hash::Hash for List<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        // Like the std collections we hash the length first, so that nested
+        // lists such as [[1], [2]] and [[1, 2]] don't hash the same values.
+        state.write_usize(self.len);
+        for element in self {
+            element.hash(state);
+        }
+    }
+}
+
+/// Serializes a List as a sequence, front to back.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for List<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self)
+    }
+}
+
+/// Deserializes a List from a sequence.
+///
+/// The elements are pushed to the back one at a time, so long sequences don't
+/// use any stack.
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for List<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ListVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: serde::Deserialize<'de>> serde::de::Visitor<'de> for ListVisitor<T> {
+            type Value = List<T>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a sequence")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<List<T>, A::Error> {
+                let mut list = List::empty();
+                while let Some(element) = seq.next_element()? {
+                    list.push_back(element);
+                }
+                Ok(list)
+            }
+        }
+
+        deserializer.deserialize_seq(ListVisitor(std::marker::PhantomData))
+    }
+}
+
+#[test]
+fn test_list_macro() {
+    let list: List<i32> = list![];
+    assert!(list.is_empty());
+
+    let mut list = list![NonCopyable(1), NonCopyable(2), NonCopyable(3),];
+    assert_eq!(list.pop(), Some(NonCopyable(1)));
+    assert_eq!(list.pop(), Some(NonCopyable(2)));
+    assert_eq!(list.pop(), Some(NonCopyable(3)));
+    assert_eq!(list.pop(), None);
+}
+
+#[test]
+fn test_from_iter_and_extend() {
+    let mut list: List<_> = (1..4).collect();
+    assert_eq!(list, list![1, 2, 3]);
+
+    list.extend(vec![4, 5]);
+    list.extend(Vec::new());
+    assert_eq!(list, list![1, 2, 3, 4, 5]);
+
+    let mut list = List::default();
+    list.extend("ab".chars());
+    assert_eq!(list, list!['a', 'b']);
+}
+
+#[test]
+fn test_clone_and_debug() {
+    let list = list!["one".to_string(), "two".to_string()];
+    let copy = list.clone();
+    assert_eq!(list, copy);
+    assert_eq!(format!("{:?}", copy), r#"["one", "two"]"#);
+    assert_eq!(format!("{:?}", List::<i32>::empty()), "[]");
+}
+
+#[test]
+fn test_eq_and_ord() {
+    assert_eq!(list![1, 2, 3], list![1, 2, 3]);
+    assert_ne!(list![1, 2, 3], list![1, 2]);
+    assert_ne!(list![1, 2], list![1, 2, 3]);
+    assert!(list![1, 2] < list![1, 2, 3]);
+    assert!(list![1, 3] > list![1, 2, 3]);
+    assert!(List::empty() < list![0]);
+    assert_eq!(list![1.0, 2.0].partial_cmp(&list![1.0, f64::NAN]), None);
+
+    let mut lists = vec![list![2], list![1, 2], list![], list![1]];
+    lists.sort();
+    assert_eq!(lists, vec![list![], list![1], list![1, 2], list![2]]);
+}
+
+#[test]
+fn test_hash() {
+    use std::collections::HashSet;
+
+    let mut set = HashSet::new();
+    assert!(set.insert(list![1, 2, 3]));
+    assert!(set.insert(list![1, 2]));
+    assert!(set.insert(List::empty()));
+    assert!(!set.insert(list![1, 2, 3]));
+    assert!(set.contains(&list![1, 2]));
+    assert!(!set.contains(&list![2, 1]));
+}
+
+#[test]
+fn test_long_list_traits() {
+    let list: List<_> = (0..1_000_000).collect();
+    let copy = list.clone();
+    assert_eq!(list, copy);
+    assert_eq!(list.cmp(&copy), std::cmp::Ordering::Equal);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_serde_tokens() {
+    use serde_test::{assert_tokens, Token};
+
+    assert_tokens(
+        &List::<NonCopyable>::empty(),
+        &[Token::Seq { len: Some(0) }, Token::SeqEnd],
+    );
+    assert_tokens(
+        &list![NonCopyable(1), NonCopyable(2)],
+        &[
+            Token::Seq { len: Some(2) },
+            Token::NewtypeStruct {
+                name: "NonCopyable",
+            },
+            Token::U32(1),
+            Token::NewtypeStruct {
+                name: "NonCopyable",
+            },
+            Token::U32(2),
+            Token::SeqEnd,
+        ],
+    );
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_serde_json() {
+    let list = list![
+        list![NonCopyable(1)],
+        List::empty(),
+        list![NonCopyable(2), NonCopyable(3)]
+    ];
+    let json = serde_json::to_string(&list).unwrap();
+    assert_eq!(json, "[[1],[],[2,3]]");
+    let back: List<List<NonCopyable>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, list);
+    assert_eq!(
+        back.peek_back(),
+        Some(&list![NonCopyable(2), NonCopyable(3)])
+    );
+
+    assert!(serde_json::from_str::<List<u32>>("[1, -2]").is_err());
+    assert!(serde_json::from_str::<List<u32>>("{}").is_err());
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_serde_long_list() {
+    let list: List<u32> = (0..1_000_000).collect();
+    let json = serde_json::to_vec(&list).unwrap();
+    let back: List<u32> = serde_json::from_slice(&json).unwrap();
+    assert_eq!(back.len(), 1_000_000);
+    assert_eq!(back, list);
+}
+
+use crate::heapsize::{HeapSize, HeapUsage};
+
+/// Counts one allocation per Node, plus whatever the elements own.
+impl<T: HeapSize> HeapSize for List<T> {
+    fn heap_size(&self) -> HeapUsage {
+        let nodes = HeapUsage {
+            allocations: self.len,
+            bytes: self.len * std::mem::size_of::<Node<T>>(),
+        };
+        nodes + self.into_iter().map(T::heap_size).sum()
+    }
+}
+
+#[test]
+fn test_push_pop_allocate_one_node() {
+    use crate::counting_alloc::{measure, Counts};
+
+    let node = std::mem::size_of::<Node<u64>>();
+    let mut list = List::empty();
+    for i in 0..3u64 {
+        let ((), counts) = measure(|| list.push(i));
+        assert_eq!(
+            counts,
+            Counts {
+                allocations: 1,
+                bytes_allocated: node,
+                ..Counts::default()
+            }
+        );
+    }
+    let ((), counts) = measure(|| list.push_back(3));
+    assert_eq!(counts.allocations, 1);
+    assert_eq!(
+        list.heap_size(),
+        HeapUsage {
+            allocations: 4,
+            bytes: 4 * node
+        }
+    );
+
+    let (popped, counts) = measure(|| list.pop());
+    assert_eq!(popped, Some(2));
+    assert_eq!(
+        counts,
+        Counts {
+            deallocations: 1,
+            bytes_deallocated: node,
+            ..Counts::default()
+        }
+    );
+}
+
+#[test]
+fn test_heap_size() {
+    use crate::counting_alloc::measure;
+
+    let (list, counts) = measure(|| (0..100).map(|i| i.to_string()).collect::<List<_>>());
+    assert_eq!(list.heap_size(), counts.live());
+    assert_eq!(List::<String>::empty().heap_size(), HeapUsage::NONE);
+}
+
+#[test]
+fn test_draining_iter_frees_every_node() {
+    use crate::counting_alloc::measure;
+
+    let list: List<String> = (0..100).map(|i| i.to_string()).collect();
+    let usage = list.heap_size();
+    let ((), counts) = measure(|| DrainingIterator(list).for_each(drop));
+    assert_eq!(counts.allocations, 0);
+    assert_eq!(counts.deallocations, usage.allocations);
+    assert_eq!(counts.bytes_deallocated, usage.bytes);
+
+    // Dropping a partly drained iterator frees the rest.
+    let list: List<String> = (0..100).map(|i| i.to_string()).collect();
+    let usage = list.heap_size();
+    let ((), counts) = measure(|| {
+        let mut iter = list.into_iter();
+        iter.nth(10);
+    });
+    assert_eq!(counts.deallocations, usage.allocations);
+    assert_eq!(counts.bytes_deallocated, usage.bytes);
+}
+
+impl<T> List<T> {
+    /// Reverses the order of the elements in place.
+    pub fn reverse(&mut self) {
+        let mut remaining = self.head.take();
+        // The first Node becomes the last one.
+        self.tail = remaining.as_ref().map(NodeBox::as_ptr);
+        while let Some(mut node) = remaining {
+            remaining = node.next.take();
+            node.next = self.head.take();
+            self.head = Some(node);
+        }
+    }
+
+    /// Sorts the list.
+    ///
+    /// This is a stable merge sort, it relinks the existing Nodes instead of
+    /// moving the elements or allocating.
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    /// Sorts the list with a comparison function, see `sort`.
+    ///
+    /// If `compare` panics the list is left empty.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> std::cmp::Ordering,
+    {
+        // The Nodes are split up into runs owned by merge_sort while it runs,
+        // so the list has to be empty in case compare panics. The runs are
+        // then dropped as the panic unwinds.
+        let len = std::mem::replace(&mut self.len, 0);
+        self.tail = None;
+        let (head, tail) = merge_sort(self.head.take(), len, &mut compare);
+        self.head = head;
+        self.tail = tail;
+        self.len = len;
+    }
+
+    /// Sorts the list with a key extraction function, see `sort`.
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Removes consecutive repeated elements.
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Removes consecutive elements that map to the same key.
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Removes consecutive elements that `same_bucket` considers equal.
+    ///
+    /// Like `Vec::dedup_by` the function is passed each element and the element
+    /// before it that was kept, and the element is removed if it returns true.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let mut current = self.head.as_mut();
+        let mut tail = None;
+        while let Some(node) = current {
+            // The first element is always kept, so this ends up pointing at
+            // the last element we kept. We only update self.tail at the end, in
+            // case same_bucket panics before we get there.
+            tail = Some(NodeBox::as_ptr(node));
+            let node = &mut **node;
+            loop {
+                let duplicate = match node.next.as_mut() {
+                    Some(next) => same_bucket(&mut next.val, &mut node.val),
+                    None => false,
+                };
+                if !duplicate {
+                    break;
+                }
+                let removed = NodeBox::into_inner(node.next.take().unwrap());
+                node.next = removed.next;
+                self.len -= 1;
+            }
+            current = node.next.as_mut();
+        }
+        self.tail = tail;
+    }
+
+    /// Keeps only the elements for which `f` returns true.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut link = &mut self.head;
+        let mut tail = None;
+        while link.is_some() {
+            if f(&link.as_ref().unwrap().val) {
+                let node = link.as_mut().unwrap();
+                tail = Some(NodeBox::as_ptr(node));
+                link = &mut node.next;
+            } else {
+                let removed = NodeBox::into_inner(link.take().unwrap());
+                *link = removed.next;
+                self.len -= 1;
+            }
+        }
+        self.tail = tail;
+    }
+}
+
+/// Sorts the `len` Nodes starting at `head` and returns the new first and
+/// last Nodes.
+//
+// This only recurses log2(len) deep so it is safe to use on long lists.
+fn merge_sort<T, F>(mut head: Link<T>, len: usize, compare: &mut F) -> (Link<T>, Tail<T>)
+where
+    F: FnMut(&T, &T) -> std::cmp::Ordering,
+{
+    if len <= 1 {
+        let tail = head.as_ref().map(NodeBox::as_ptr);
+        return (head, tail);
+    }
+    let middle = len / 2;
+    let mut link = &mut head;
+    for _ in 0..middle {
+        link = &mut link.as_mut().unwrap().next;
+    }
+    let back = link.take();
+    let front = merge_sort(head, middle, compare);
+    let back = merge_sort(back, len - middle, compare);
+    merge(front, back, compare)
+}
+
+/// Merges two sorted runs of Nodes, each with its last Node, into one.
+///
+/// When elements compare equal the one from `front` goes first, which is what
+/// makes the sort stable.
+fn merge<T, F>(
+    (mut front, front_tail): (Link<T>, Tail<T>),
+    (mut back, back_tail): (Link<T>, Tail<T>),
+    compare: &mut F,
+) -> (Link<T>, Tail<T>)
+where
+    F: FnMut(&T, &T) -> std::cmp::Ordering,
+{
+    let mut merged = None;
+    let mut end = &mut merged;
+    while let (Some(a), Some(b)) = (&front, &back) {
+        let source = if compare(&a.val, &b.val) == std::cmp::Ordering::Greater {
+            &mut back
+        } else {
+            &mut front
+        };
+        let mut node = source.take().unwrap();
+        *source = node.next.take();
+        end = &mut end.insert(node).next;
+    }
+    // The run that is left over ends the merged run.
+    let tail = if front.is_some() {
+        *end = front;
+        front_tail
+    } else {
+        *end = back;
+        back_tail
+    };
+    (merged, tail)
+}
+
+#[test]
+fn test_reverse() {
+    let mut list: List<i32> = list![];
+    list.reverse();
+    assert_eq!(list, list![]);
+    assert_eq!(list.peek_back(), None);
+
+    let mut list = list![NonCopyable(1), NonCopyable(2), NonCopyable(3)];
+    list.reverse();
+    assert_eq!(list, list![NonCopyable(3), NonCopyable(2), NonCopyable(1)]);
+    assert_eq!(list.peek_back(), Some(&NonCopyable(1)));
+    list.push_back(NonCopyable(0));
+    assert_eq!(list.len(), 4);
+    assert_eq!(list.peek_back(), Some(&NonCopyable(0)));
+}
+
+#[test]
+fn test_sort() {
+    let mut list: List<i32> = list![];
+    list.sort();
+    assert_eq!(list, list![]);
+
+    let mut list = list![5, 1, 4, 2, 3, 1];
+    list.sort();
+    assert_eq!(list, list![1, 1, 2, 3, 4, 5]);
+    assert_eq!(list.peek_back(), Some(&5));
+    list.push_back(6);
+    assert_eq!(list.len(), 7);
+
+    list.sort_by(|a, b| b.cmp(a));
+    assert_eq!(list, list![6, 5, 4, 3, 2, 1, 1]);
+    assert_eq!(list.peek_back(), Some(&1));
+}
+
+#[test]
+fn test_sort_is_stable() {
+    let mut list = list![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')];
+    list.sort_by_key(|&(key, _)| key);
+    assert_eq!(
+        list,
+        list![(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]
+    );
+}
+
+#[test]
+fn test_sort_long_list() {
+    // A simple linear congruential generator is enough to shuffle the input.
+    let mut state: u32 = 1;
+    let mut list: List<u32> = (0..1_000_000)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            state >> 8
+        })
+        .collect();
+    let mut expected: Vec<_> = (&list).into_iter().cloned().collect();
+    expected.sort();
+
+    list.sort();
+    assert_eq!(list.len(), expected.len());
+    assert!((&list).into_iter().eq(&expected));
+}
+
+#[test]
+fn test_sort_panic() {
+    // Long enough that dropping the runs recursively would overflow the stack.
+    let len = if cfg!(miri) { 1_000 } else { 1_000_000 };
+    let mut list: List<_> = (0..len).rev().collect();
+    let mut comparisons = 0;
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        list.sort_by(|a, b| {
+            comparisons += 1;
+            if comparisons == len {
+                panic!("comparison failed");
+            }
+            a.cmp(b)
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(list.len(), 0);
+    assert_eq!((&list).into_iter().count(), 0);
+    assert_eq!(list.peek_back(), None);
+    list.push_back(1);
+    assert_eq!(list, list![1]);
+}
+
+#[test]
+fn test_dedup() {
+    let mut list = list![1, 1, 2, 3, 3, 3, 1, 4, 4];
+    list.dedup();
+    assert_eq!(list, list![1, 2, 3, 1, 4]);
+    assert_eq!(list.len(), 5);
+    assert_eq!(list.peek_back(), Some(&4));
+    list.push_back(5);
+    assert_eq!(list.peek_back(), Some(&5));
+
+    let mut list = list![10, 11, 20, 21, 22, 30];
+    list.dedup_by_key(|n| *n / 10);
+    assert_eq!(list, list![10, 20, 30]);
+
+    let mut list: List<i32> = list![];
+    list.dedup();
+    assert!(list.is_empty());
+}
+
+#[test]
+fn test_dedup_panic() {
+    let mut list = list![1, 1, 2, 3, 3];
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        list.dedup_by(|a, b| {
+            if *a == 3 {
+                panic!("comparison failed");
+            }
+            a == b
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(list.len(), (&list).into_iter().count());
+    list.push_back(4);
+    assert_eq!(list, list![1, 2, 3, 3, 4]);
+}
+
+#[test]
+fn test_retain() {
+    let mut list = list![1, 2, 3, 4, 5, 6];
+    list.retain(|n| n % 2 == 1);
+    assert_eq!(list, list![1, 3, 5]);
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.peek_back(), Some(&5));
+
+    list.retain(|n| *n < 5);
+    assert_eq!(list.peek_back(), Some(&3));
+    list.push_back(7);
+    assert_eq!(list, list![1, 3, 7]);
+
+    list.retain(|_| false);
+    assert!(list.is_empty());
+    assert_eq!(list.peek_back(), None);
+    list.push_back(8);
+    assert_eq!(list, list![8]);
+}
+
+#[test]
+fn test_peek_back_after_every_mutation() {
+    // The tail has to point at the very Node that a walk of the list ends on,
+    // not just at an equal value.
+    fn check(list: &List<i32>) {
+        assert_eq!(
+            list.peek_back().map(|val| val as *const i32),
+            list.into_iter().last().map(|val| val as *const i32)
+        );
+    }
+    fn at(list: &mut List<i32>, index: usize) -> CursorMut<'_, i32> {
+        let mut cursor = list.cursor_front_mut();
+        for _ in 0..index {
+            cursor.move_next();
+        }
+        cursor
+    }
+
+    let mut list = list![1, 2, 3];
+    list.pop();
+    check(&list);
+    list.pop();
+    list.pop();
+    check(&list);
+    list.push(1);
+    check(&list);
+
+    at(&mut list, 0).insert_after(2);
+    check(&list);
+    at(&mut list, 2).insert_before(3);
+    check(&list);
+    at(&mut list, 0).insert_before(0);
+    check(&list);
+    assert_eq!(at(&mut list, 3).remove_current(), Some(3));
+    check(&list);
+    assert_eq!(at(&mut list, 3).remove_current(), None);
+    check(&list);
+    assert_eq!(at(&mut list, 1).remove_current(), Some(1));
+    check(&list);
+    assert_eq!(list, list![0, 2]);
+
+    let split = at(&mut list, 1).split_after();
+    check(&list);
+    check(&split);
+    let split = at(&mut list, 0).split_after();
+    check(&list);
+    check(&split);
+    at(&mut list, 0).splice_after(split);
+    check(&list);
+    at(&mut list, 1).splice_after(list![5, 4]);
+    check(&list);
+    at(&mut list, 4).splice_after(list![3]);
+    check(&list);
+    at(&mut list, 1).splice_after(list![]);
+    check(&list);
+    assert_eq!(list, list![0, 2, 5, 4, 3]);
+
+    list.reverse();
+    check(&list);
+    list.sort();
+    check(&list);
+    list.sort_by(|a, b| b.cmp(a));
+    check(&list);
+    list.sort_by_key(|n| n % 3);
+    check(&list);
+    assert_eq!(list, list![3, 0, 4, 5, 2]);
+
+    list.push_back(2);
+    list.dedup();
+    check(&list);
+    list.push_back(3);
+    list.dedup_by_key(|n| *n / 2);
+    check(&list);
+    list.dedup_by(|a, b| a < b);
+    check(&list);
+    assert_eq!(list, list![3, 4]);
+
+    list.retain(|n| *n != 4);
+    check(&list);
+    list.retain(|n| *n != 3);
+    check(&list);
+
+    let mut other = list![4];
+    list.append(&mut other);
+    check(&list);
+    check(&other);
+    other.append(&mut list);
+    check(&list);
+    check(&other);
+    other.append(&mut list![6]);
+    check(&other);
+    other.push_back(7);
+    assert_eq!(other, list![4, 6, 7]);
+}
+
+use crate::sequence::Sequence;
+
+/// The result of a game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Score {
+    pub home_points: u32,
+    pub away_points: u32,
+}
+
+/// Which side of a game did better.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Team {
+    Draw,
+    Home,
+    Away,
+}
+
+impl Score {
+    /// Returns the Team with more points, or Draw if they have the same.
+    pub fn winner(&self) -> Team {
+        winner_of(self.home_points, self.away_points)
+    }
+}
+
+/// Returns the Team with more points, or Draw if they have the same.
+fn winner_of<N: Ord>(home_points: N, away_points: N) -> Team {
+    match home_points.cmp(&away_points) {
+        std::cmp::Ordering::Greater => Team::Home,
+        std::cmp::Ordering::Equal => Team::Draw,
+        std::cmp::Ordering::Less => Team::Away,
+    }
+}
+
+// Totals can overflow a u32 with enough games. There is a variant of
+// total_score for each way to handle that:
+//
+// - total_score panics, in release builds as well as debug ones, rather than
+//   returning a wrong total.
+// - checked_total_score returns an OverflowError.
+// - saturating_total_score stops at u32::MAX.
+// - wide_total_score adds up in u64, which can't overflow for fewer than 2^32
+//   games.
+//
+// highest_total_score compares the wide totals, so it is right for any input.
+
+/// Returns the total score for the home and away teams across games.
+///
+/// Given a List<Score> representing the scores for a sequence of games, produce
+/// a Score representing the total of those scores.
+///
+/// Any Sequence of scores works, such as a Vec or a linkedlist::List.
+///
+/// Panics if either total is more than u32::MAX.
+pub fn total_score<S: Sequence<Score>>(results: &S) -> Score {
+    // Hint: You can use a for loop to iterate over the results.
+    // https://doc.rust-lang.org/1.1.0/book/for-loops.html
+    //
+    // Hint: Or you could call `results.into_iter()` and use any of the methods
+    // provided by the iterator trait.
+    // https://doc.rust-lang.org/std/iter/trait.Iterator.html
+    // unimplemented!("codelab::total_score()");
+    match checked_total_score(results) {
+        Ok(total) => total,
+        Err(e) => panic!("total_score: {}", e),
+    }
+}
+
+/// The error returned by checked_total_score when a total doesn't fit in a
+/// u32.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OverflowError {
+    game: usize,
+    team: Team,
+}
+
+impl OverflowError {
+    /// Returns the index of the game whose points overflowed the total.
+    pub fn game(&self) -> usize {
+        self.game
+    }
+
+    /// Returns the side whose total overflowed, Home or Away. If both did this
+    /// is Home.
+    pub fn team(&self) -> Team {
+        self.team
+    }
+}
+
+impl std::fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let side = match self.team {
+            Team::Away => "away",
+            _ => "home",
+        };
+        write!(f, "{} points overflow at game {}", side, self.game)
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+/// Returns the total score across games, or an error if either total is more
+/// than u32::MAX.
+pub fn checked_total_score<S: Sequence<Score>>(results: &S) -> Result<Score, OverflowError> {
+    results.iter().enumerate().try_fold(
+        Score {
+            home_points: 0,
+            away_points: 0,
+        },
+        |total, (game, result)| {
+            let overflow = |team| OverflowError { game, team };
+            Ok(Score {
+                home_points: total
+                    .home_points
+                    .checked_add(result.home_points)
+                    .ok_or_else(|| overflow(Team::Home))?,
+                away_points: total
+                    .away_points
+                    .checked_add(result.away_points)
+                    .ok_or_else(|| overflow(Team::Away))?,
+            })
+        },
+    )
+}
+
+/// Returns the total score across games, with totals that would be more than
+/// u32::MAX replaced by u32::MAX.
+pub fn saturating_total_score<S: Sequence<Score>>(results: &S) -> Score {
+    results.iter().fold(
+        Score {
+            home_points: 0,
+            away_points: 0,
+        },
+        |total, result| Score {
+            home_points: total.home_points.saturating_add(result.home_points),
+            away_points: total.away_points.saturating_add(result.away_points),
+        },
+    )
+}
+
+/// A Score with room for totals over many games.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WideScore {
+    pub home_points: u64,
+    pub away_points: u64,
+}
+
+impl WideScore {
+    /// Returns the Team with more points, or Draw if they have the same.
+    pub fn winner(&self) -> Team {
+        winner_of(self.home_points, self.away_points)
+    }
+}
+
+impl From<Score> for WideScore {
+    fn from(score: Score) -> Self {
+        WideScore {
+            home_points: score.home_points.into(),
+            away_points: score.away_points.into(),
+        }
+    }
+}
+
+/// Returns the total score across games as u64s.
+pub fn wide_total_score<S: Sequence<Score>>(results: &S) -> WideScore {
+    results.iter().fold(WideScore::default(), |total, &result| {
+        let result = WideScore::from(result);
+        WideScore {
+            home_points: total.home_points + result.home_points,
+            away_points: total.away_points + result.away_points,
+        }
+    })
+}
+
+#[test]
+fn test_09_total_score() {
+    let mut scores = List::empty();
+    assert_eq!(
+        total_score(&scores),
+        Score {
+            home_points: 0,
+            away_points: 0
+        }
+    );
+
+    scores.push(Score {
+        home_points: 0,
+        away_points: 0,
+    });
+    assert_eq!(
+        total_score(&scores),
+        Score {
+            home_points: 0,
+            away_points: 0
+        }
+    );
+
+    scores.push(Score {
+        home_points: 3,
+        away_points: 0,
+    });
+    assert_eq!(
+        total_score(&scores),
+        Score {
+            home_points: 3,
+            away_points: 0
+        }
+    );
+
+    scores.push(Score {
+        home_points: 0,
+        away_points: 3,
+    });
+    assert_eq!(
+        total_score(&scores),
+        Score {
+            home_points: 3,
+            away_points: 3
+        }
+    );
+
+    scores.push(Score {
+        home_points: 2,
+        away_points: 1,
+    });
+    assert_eq!(
+        total_score(&scores),
+        Score {
+            home_points: 5,
+            away_points: 4
+        }
+    );
+}
+
+/// Returns the Team with the highest score over all the games in the list.
+pub fn highest_total_score<S: Sequence<Score>>(results: &S) -> Team {
+    // Hint: total_score() might be useful :)
+    // unimplemented!("codelab::highest_total_score()");
+    wide_total_score(results).winner()
+}
+
+#[test]
+fn test_10_highest_total_score() {
+    let mut scores = List::empty();
+    assert_eq!(highest_total_score(&scores), Team::Draw);
+
+    scores.push(Score {
+        home_points: 0,
+        away_points: 0,
+    });
+    assert_eq!(highest_total_score(&scores), Team::Draw);
+
+    scores.push(Score {
+        home_points: 3,
+        away_points: 0,
+    });
+    assert_eq!(highest_total_score(&scores), Team::Home);
+
+    scores.push(Score {
+        home_points: 0,
+        away_points: 3,
+    });
+    assert_eq!(highest_total_score(&scores), Team::Draw);
+
+    scores.push(Score {
+        home_points: 2,
+        away_points: 1,
+    });
+    assert_eq!(highest_total_score(&scores), Team::Home);
+}
+
+#[test]
+fn test_total_score_overflow() {
+    let score = |home_points, away_points| Score {
+        home_points,
+        away_points,
+    };
+    let max = u32::MAX;
+
+    // Exactly u32::MAX fits.
+    let scores = vec![score(max - 1, 0), score(1, max)];
+    assert_eq!(total_score(&scores), score(max, max));
+    assert_eq!(checked_total_score(&scores), Ok(score(max, max)));
+    assert_eq!(saturating_total_score(&scores), score(max, max));
+    assert_eq!(wide_total_score(&scores), WideScore::from(score(max, max)));
+
+    // One more doesn't.
+    let scores = vec![score(3, max), score(0, 0), score(0, 1)];
+    let e = checked_total_score(&scores).unwrap_err();
+    assert_eq!((e.game(), e.team()), (2, Team::Away));
+    assert_eq!(e.to_string(), "away points overflow at game 2");
+    assert_eq!(saturating_total_score(&scores), score(3, max));
+    assert_eq!(
+        wide_total_score(&scores),
+        WideScore {
+            home_points: 3,
+            away_points: max as u64 + 1,
+        }
+    );
+
+    let scores = vec![score(max, max), score(max, max), score(1, 0)];
+    let e = checked_total_score(&scores).unwrap_err();
+    assert_eq!((e.game(), e.team()), (1, Team::Home));
+    assert_eq!(saturating_total_score(&scores), score(max, max));
+    assert_eq!(
+        wide_total_score(&scores),
+        WideScore {
+            home_points: 2 * max as u64 + 1,
+            away_points: 2 * max as u64,
+        }
+    );
+    // Saturated totals would be a draw.
+    assert_eq!(highest_total_score(&scores), Team::Home);
+}
+
+#[test]
+#[should_panic(expected = "total_score: home points overflow at game 1")]
+fn test_total_score_panics() {
+    let scores = vec![
+        Score {
+            home_points: u32::MAX,
+            away_points: 0,
+        },
+        Score {
+            home_points: 1,
+            away_points: 0,
+        },
+    ];
+    total_score(&scores);
+}
+
+/// Returns the games where the home team wins!
+///
+/// Given a List<Score> returns one with only the winning games, in the same
+/// order as they were in `results`.
+pub fn games_worth_watching<S: Sequence<Score>>(results: S) -> S {
+    // unimplemented!("codelab::games_worth_watching()");
+    results
+        .into_iter()
+        .filter(|r| r.home_points > r.away_points)
+        .collect()
+}
+
+#[test]
+fn test_11_games_worth_watching() {
+    let mut scores = List::empty();
+    scores.push(Score {
+        home_points: 0,
+        away_points: 0,
+    });
+    scores.push(Score {
+        home_points: 0,
+        away_points: 3,
+    });
+    let mut good = games_worth_watching(scores);
+    assert_eq!(good.pop(), None);
+
+    let mut scores = List::empty();
+    scores.push(Score {
+        home_points: 0,
+        away_points: 0,
+    });
+    scores.push(Score {
+        home_points: 5,
+        away_points: 3,
+    });
     scores.push(Score {
         home_points: 0,
         away_points: 3,
@@ -532,8 +2348,7 @@ fn test_11_games_worth_watching() {
         home_points: 1,
         away_points: 0,
     });
-    let mut good: Vec<_> = games_worth_watching(scores).into_iter().collect();
-    good.sort_by(|a, b| a.home_points.cmp(&b.home_points));
+    let good: Vec<_> = games_worth_watching(scores).into_iter().collect();
     assert_eq!(
         good,
         vec![
@@ -549,5 +2364,44 @@ fn test_11_games_worth_watching() {
     );
 }
 
+#[test]
+fn test_scores_over_sequences() {
+    fn scores<S: Sequence<Score>>() -> S {
+        vec![(1, 0), (0, 3), (4, 2), (0, 0)]
+            .into_iter()
+            .map(|(home_points, away_points)| Score {
+                home_points,
+                away_points,
+            })
+            .collect()
+    }
+    fn check<S: Sequence<Score>>() {
+        let total = Score {
+            home_points: 5,
+            away_points: 5,
+        };
+        assert_eq!(total_score(&scores::<S>()), total);
+        assert_eq!(highest_total_score(&scores::<S>()), Team::Draw);
+        let good: Vec<_> = games_worth_watching(scores::<S>()).into_iter().collect();
+        assert_eq!(
+            good,
+            vec![
+                Score {
+                    home_points: 1,
+                    away_points: 0
+                },
+                Score {
+                    home_points: 4,
+                    away_points: 2
+                },
+            ]
+        );
+    }
+
+    check::<List<Score>>();
+    check::<Vec<Score>>();
+    check::<crate::linkedlist::List<Score>>();
+}
+
 // Congratulations! You have made it to the end of the codelab, I hope you enjoyed Rust 101.
 // Remember to mark you attendance in Grow: http://go/iamhere
\ No newline at end of file
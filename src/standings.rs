@@ -0,0 +1,329 @@
+// League standings.
+//
+// solution::highest_total_score only tells us whether the home or the away
+// teams did better overall. Here each Game also names its two teams, and
+// `standings` turns a season of Games into a league table: one Row per team
+// with its results, goals and points, ordered by points and then by the
+// tiebreakers in the Rules.
+//
+// The points in a Score are the goals each side scored. The points in the table
+// are the ones awarded for the result.
+
+use crate::solution::{Score, Team};
+use std::collections::BTreeMap;
+
+/// A game between two named teams.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    pub home: String,
+    pub away: String,
+    pub score: Score,
+}
+
+impl Game {
+    pub fn new(home: &str, away: &str, home_points: u32, away_points: u32) -> Self {
+        Game {
+            home: home.to_string(),
+            away: away.to_string(),
+            score: Score {
+                home_points,
+                away_points,
+            },
+        }
+    }
+}
+
+/// The table points awarded for each result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Points {
+    pub win: u32,
+    pub draw: u32,
+    pub loss: u32,
+}
+
+impl Points {
+    /// 3 points for a win and 1 for a draw, what most leagues use today.
+    pub const THREE_ONE_ZERO: Points = Points {
+        win: 3,
+        draw: 1,
+        loss: 0,
+    };
+
+    /// 2 points for a win and 1 for a draw, the older system.
+    pub const TWO_ONE_ZERO: Points = Points {
+        win: 2,
+        draw: 1,
+        loss: 0,
+    };
+}
+
+/// A way to order teams that have the same number of points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tiebreaker {
+    /// Most points in the games between the tied teams.
+    HeadToHead,
+    /// Highest goals for minus goals against.
+    GoalDifference,
+    /// Most goals for.
+    GoalsFor,
+}
+
+/// How to build a table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    pub points: Points,
+    /// Applied in order to teams that are still tied. Teams that are tied
+    /// after all of them are ordered by name.
+    pub tiebreakers: Vec<Tiebreaker>,
+}
+
+/// 3-1-0, then goal difference, goals for and head-to-head.
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            points: Points::THREE_ONE_ZERO,
+            tiebreakers: vec![
+                Tiebreaker::GoalDifference,
+                Tiebreaker::GoalsFor,
+                Tiebreaker::HeadToHead,
+            ],
+        }
+    }
+}
+
+/// One team's line in the table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Row {
+    pub team: String,
+    pub played: u32,
+    pub won: u32,
+    pub drawn: u32,
+    pub lost: u32,
+    pub goals_for: u64,
+    pub goals_against: u64,
+    pub points: u64,
+}
+
+impl Row {
+    pub fn goal_difference(&self) -> i64 {
+        self.goals_for as i64 - self.goals_against as i64
+    }
+
+    fn record(&mut self, scored: u32, conceded: u32, result: Team, side: Team, points: &Points) {
+        self.played += 1;
+        self.goals_for += scored as u64;
+        self.goals_against += conceded as u64;
+        let awarded = if result == Team::Draw {
+            self.drawn += 1;
+            points.draw
+        } else if result == side {
+            self.won += 1;
+            points.win
+        } else {
+            self.lost += 1;
+            points.loss
+        };
+        self.points += awarded as u64;
+    }
+}
+
+/// Returns the league table for `games`, best team first.
+pub fn standings<'a, I: IntoIterator<Item = &'a Game>>(games: I, rules: &Rules) -> Vec<Row> {
+    let games: Vec<&Game> = games.into_iter().collect();
+    let mut rows = tally(&games, &rules.points);
+
+    let criteria: Vec<Criterion> = std::iter::once(Criterion::Points)
+        .chain(rules.tiebreakers.iter().copied().map(Criterion::Tiebreaker))
+        .collect();
+    let table = Table {
+        games: &games,
+        rows: &rows,
+        points: &rules.points,
+    };
+    let mut order = Vec::with_capacity(rows.len());
+    table.rank(rows.keys().copied().collect(), &criteria, &mut order);
+
+    order
+        .into_iter()
+        .map(|team| rows.remove(team).expect("ranked team has a row"))
+        .collect()
+}
+
+/// Returns a Row for every team that played in `games`.
+fn tally<'a>(games: &[&'a Game], points: &Points) -> BTreeMap<&'a str, Row> {
+    fn row<'r, 'a>(rows: &'r mut BTreeMap<&'a str, Row>, team: &'a str) -> &'r mut Row {
+        rows.entry(team).or_insert_with(|| Row {
+            team: team.to_string(),
+            ..Row::default()
+        })
+    }
+
+    let mut rows = BTreeMap::new();
+    for game in games {
+        let Score {
+            home_points,
+            away_points,
+        } = game.score;
+        let result = game.score.winner();
+        row(&mut rows, &game.home).record(home_points, away_points, result, Team::Home, points);
+        row(&mut rows, &game.away).record(away_points, home_points, result, Team::Away, points);
+    }
+    rows
+}
+
+/// What `Table::rank` orders a group of teams by.
+#[derive(Clone, Copy)]
+enum Criterion {
+    Points,
+    Tiebreaker(Tiebreaker),
+}
+
+struct Table<'a, 'b> {
+    games: &'b [&'a Game],
+    rows: &'b BTreeMap<&'a str, Row>,
+    points: &'b Points,
+}
+
+impl<'a, 'b> Table<'a, 'b> {
+    /// Appends `group`, which is sorted by name, to `order`, best team first.
+    ///
+    /// The teams are sorted by the first criterion, and each set of teams that
+    /// is still tied is ranked by the remaining criteria.
+    fn rank(&self, group: Vec<&'a str>, criteria: &[Criterion], order: &mut Vec<&'a str>) {
+        let (criterion, rest) = match criteria.split_first() {
+            Some(split) if group.len() > 1 => split,
+            _ => {
+                order.extend(group);
+                return;
+            }
+        };
+        let keys = self.keys(*criterion, &group);
+        let mut keyed: Vec<(i64, &'a str)> = keys.into_iter().zip(group).collect();
+        // Stable, so tied teams stay in name order.
+        keyed.sort_by_key(|&(key, _)| std::cmp::Reverse(key));
+        for tied in keyed.chunk_by(|a, b| a.0 == b.0) {
+            let tied = tied.iter().map(|&(_, team)| team).collect();
+            self.rank(tied, rest, order);
+        }
+    }
+
+    /// Returns the value of `criterion` for each team in `group`, higher is
+    /// better.
+    fn keys(&self, criterion: Criterion, group: &[&str]) -> Vec<i64> {
+        let row = |team: &str| &self.rows[team];
+        match criterion {
+            Criterion::Points => group.iter().map(|t| row(t).points as i64).collect(),
+            Criterion::Tiebreaker(Tiebreaker::GoalDifference) => {
+                group.iter().map(|t| row(t).goal_difference()).collect()
+            }
+            Criterion::Tiebreaker(Tiebreaker::GoalsFor) => {
+                group.iter().map(|t| row(t).goals_for as i64).collect()
+            }
+            Criterion::Tiebreaker(Tiebreaker::HeadToHead) => {
+                let between: Vec<&Game> = self
+                    .games
+                    .iter()
+                    .copied()
+                    .filter(|g| group.contains(&&*g.home) && group.contains(&&*g.away))
+                    .collect();
+                let mini = tally(&between, self.points);
+                group
+                    .iter()
+                    .map(|t| mini.get(t).map_or(0, |row| row.points as i64))
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn teams(table: &[Row]) -> Vec<&str> {
+    table.iter().map(|row| &*row.team).collect()
+}
+
+#[test]
+fn test_rows() {
+    let games = vec![
+        Game::new("Ajax", "Benfica", 3, 1),
+        Game::new("Benfica", "Celtic", 2, 2),
+        Game::new("Celtic", "Ajax", 1, 0),
+        Game::new("Benfica", "Ajax", 0, 0),
+    ];
+    let table = standings(&games, &Rules::default());
+    assert_eq!(teams(&table), vec!["Celtic", "Ajax", "Benfica"]);
+    assert_eq!(
+        table[1],
+        Row {
+            team: "Ajax".to_string(),
+            played: 3,
+            won: 1,
+            drawn: 1,
+            lost: 1,
+            goals_for: 3,
+            goals_against: 2,
+            points: 4,
+        }
+    );
+    assert_eq!(table[1].goal_difference(), 1);
+    assert_eq!(table[2].goal_difference(), -2);
+    assert_eq!(table[0].points, 4);
+    assert!(standings(&[], &Rules::default()).is_empty());
+}
+
+#[test]
+fn test_points_systems() {
+    // Ada wins one and loses two, Cy draws all three.
+    let games = vec![
+        Game::new("Ada", "Bo", 5, 0),
+        Game::new("Ada", "Di", 0, 1),
+        Game::new("Ada", "Ed", 0, 1),
+        Game::new("Cy", "Bo", 0, 0),
+        Game::new("Cy", "Di", 0, 0),
+        Game::new("Cy", "Ed", 0, 0),
+    ];
+    let three = standings(&games, &Rules::default());
+    assert_eq!(teams(&three), vec!["Di", "Ed", "Ada", "Cy", "Bo"]);
+    assert_eq!(three[2].points, 3);
+
+    let two = Rules {
+        points: Points::TWO_ONE_ZERO,
+        ..Rules::default()
+    };
+    let two = standings(&games, &two);
+    assert_eq!(teams(&two), vec!["Di", "Ed", "Cy", "Ada", "Bo"]);
+    assert_eq!(two[3].points, 2);
+}
+
+#[test]
+fn test_tiebreakers() {
+    // Wes, Xan and Yul all have 3 points. Xan has the best goal difference,
+    // but lost to Yul, who lost to Wes.
+    let games = vec![
+        Game::new("Yul", "Xan", 1, 0),
+        Game::new("Xan", "Zed", 5, 0),
+        Game::new("Wes", "Yul", 1, 0),
+    ];
+    let rules = |tiebreakers| Rules {
+        points: Points::THREE_ONE_ZERO,
+        tiebreakers,
+    };
+    use Tiebreaker::*;
+
+    let table = standings(&games, &rules(vec![GoalDifference, HeadToHead]));
+    assert_eq!(teams(&table), vec!["Xan", "Wes", "Yul", "Zed"]);
+
+    // Head-to-head between the three puts Xan last, then Wes and Yul are
+    // separated by goal difference.
+    let table = standings(&games, &rules(vec![HeadToHead, GoalDifference]));
+    assert_eq!(teams(&table), vec!["Wes", "Yul", "Xan", "Zed"]);
+
+    // Without tiebreakers ties are broken by name.
+    let table = standings(&games, &rules(vec![]));
+    assert_eq!(teams(&table), vec!["Wes", "Xan", "Yul", "Zed"]);
+
+    let games = vec![Game::new("Ann", "Bea", 1, 1), Game::new("Cat", "Dot", 3, 3)];
+    let table = standings(&games, &rules(vec![GoalsFor]));
+    assert_eq!(teams(&table), vec!["Cat", "Dot", "Ann", "Bea"]);
+    let table = standings(&games, &rules(vec![GoalDifference]));
+    assert_eq!(teams(&table), vec!["Ann", "Bea", "Cat", "Dot"]);
+}
@@ -0,0 +1,400 @@
+// Lazy streams.
+//
+// A Stream is a linked list like linkedlist::List, except that the tail of each
+// Node isn't computed until someone asks for it. This lets a Stream represent
+// infinite sequences such as the natural numbers: only the Nodes that are
+// actually looked at are ever built.
+//
+// Each tail is a memoized thunk. The first time it is forced the closure runs
+// and its result is stored in place of the closure, so forcing it again, or
+// forcing it through a clone of the Stream, returns the same Nodes without
+// running anything. Nodes are shared through Rc, so cloning a Stream is O(1).
+//
+// The head of each Node is computed eagerly, only the tail is lazy. The
+// combinators build their result one Node at a time as the tails are forced, so
+// even though they are defined in terms of themselves they never recurse more
+// than one level deep.
+
+use crate::linkedlist::List;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Lazily evaluated, possibly infinite, list.
+pub struct Stream<T> {
+    head: Option<Rc<Node<T>>>,
+}
+
+struct Node<T> {
+    element: T,
+    tail: Thunk<T>,
+}
+
+struct Thunk<T> {
+    state: RefCell<ThunkState<T>>,
+}
+
+enum ThunkState<T> {
+    Pending(Box<dyn FnOnce() -> Stream<T>>),
+    // The closure is running. Seeing this again means the tail depends on
+    // itself.
+    Forcing,
+    Forced(Stream<T>),
+    // The closure panicked, so there is no tail.
+    Poisoned,
+}
+
+/// Poisons the thunk if it is dropped while the closure is still running, which
+/// means the closure panicked.
+struct PoisonOnUnwind<'a, T>(&'a Thunk<T>);
+
+impl<'a, T> Drop for PoisonOnUnwind<'a, T> {
+    fn drop(&mut self) {
+        // The borrow is always free here, the panic has already unwound past
+        // any force of this thunk, but don't risk a second panic.
+        if let Ok(mut state) = self.0.state.try_borrow_mut() {
+            *state = ThunkState::Poisoned;
+        }
+    }
+}
+
+impl<T> Thunk<T> {
+    /// Returns the tail, running the closure if this is the first time.
+    fn force(&self) -> Stream<T> {
+        let mut state = self.state.borrow_mut();
+        if let ThunkState::Pending(_) = &*state {
+            let f = match std::mem::replace(&mut *state, ThunkState::Forcing) {
+                ThunkState::Pending(f) => f,
+                _ => unreachable!(),
+            };
+            // Other thunks may be forced while computing this one, so don't
+            // hold the borrow while the closure runs.
+            drop(state);
+            let poison = PoisonOnUnwind(self);
+            let tail = f();
+            std::mem::forget(poison);
+            state = self.state.borrow_mut();
+            *state = ThunkState::Forced(tail);
+        }
+        match &*state {
+            ThunkState::Forced(tail) => tail.clone(),
+            ThunkState::Forcing => panic!("Stream tail depends on itself"),
+            ThunkState::Poisoned => panic!("Stream tail panicked when it was forced before"),
+            ThunkState::Pending(_) => unreachable!(),
+        }
+    }
+}
+
+impl<T> Stream<T> {
+    /// Creates an empty stream.
+    pub fn empty() -> Self {
+        Stream { head: None }
+    }
+
+    /// Creates a stream of `element` followed by the stream `tail` returns.
+    ///
+    /// `tail` isn't called until the tail is needed, and then only once.
+    pub fn cons<F>(element: T, tail: F) -> Self
+    where
+        F: FnOnce() -> Stream<T> + 'static,
+    {
+        Stream {
+            head: Some(Rc::new(Node {
+                element,
+                tail: Thunk {
+                    state: RefCell::new(ThunkState::Pending(Box::new(tail))),
+                },
+            })),
+        }
+    }
+
+    /// Returns true iff the stream is empty.
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Returns the first element.
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.element)
+    }
+
+    /// Returns the stream without its first element, computing it if needed.
+    ///
+    /// The tail of the empty stream is the empty stream.
+    pub fn tail(&self) -> Self {
+        match &self.head {
+            Some(node) => node.tail.force(),
+            None => Stream::empty(),
+        }
+    }
+
+    /// Returns an iterator over clones of the elements.
+    ///
+    /// For an infinite stream the iterator never ends.
+    pub fn iter(&self) -> Iter<T> {
+        Iter {
+            current: self.clone(),
+        }
+    }
+}
+
+impl<T: 'static> Stream<T> {
+    /// Returns a stream of the results of calling `f` on each element.
+    pub fn map<U: 'static, F: Fn(&T) -> U + 'static>(&self, f: F) -> Stream<U> {
+        fn map_rc<T: 'static, U: 'static>(stream: &Stream<T>, f: Rc<dyn Fn(&T) -> U>) -> Stream<U> {
+            match stream.head() {
+                None => Stream::empty(),
+                Some(element) => {
+                    let stream = stream.clone();
+                    Stream::cons(f(element), move || map_rc(&stream.tail(), f))
+                }
+            }
+        }
+        map_rc(self, Rc::new(f))
+    }
+}
+
+impl<T: Clone + 'static> Stream<T> {
+    /// Creates the infinite stream `seed, f(seed), f(f(seed)), ...`.
+    pub fn iterate<F: Fn(&T) -> T + 'static>(seed: T, f: F) -> Self {
+        fn iterate_rc<T: Clone + 'static>(element: T, f: Rc<dyn Fn(&T) -> T>) -> Stream<T> {
+            let previous = element.clone();
+            Stream::cons(element, move || iterate_rc(f(&previous), f))
+        }
+        iterate_rc(seed, Rc::new(f))
+    }
+
+    /// Returns a stream of the first `n` elements.
+    pub fn take(&self, n: usize) -> Self {
+        match self.head() {
+            Some(element) if n > 0 => {
+                let stream = self.clone();
+                // Don't force the tail after the last element, it may be
+                // expensive or even fail to compute.
+                Stream::cons(element.clone(), move || match n {
+                    1 => Stream::empty(),
+                    _ => stream.tail().take(n - 1),
+                })
+            }
+            _ => Stream::empty(),
+        }
+    }
+
+    /// Returns a stream of the elements for which `f` returns true.
+    ///
+    /// This searches for the first matching element straight away, and for
+    /// each following one when the tail is forced. On an infinite stream that
+    /// has no more matches that search never ends.
+    pub fn filter<F: Fn(&T) -> bool + 'static>(&self, f: F) -> Self {
+        fn filter_rc<T: Clone + 'static>(
+            stream: &Stream<T>,
+            f: Rc<dyn Fn(&T) -> bool>,
+        ) -> Stream<T> {
+            let mut current = stream.clone();
+            loop {
+                let element = match current.head() {
+                    None => return Stream::empty(),
+                    Some(element) if f(element) => element.clone(),
+                    Some(_) => {
+                        current = current.tail();
+                        continue;
+                    }
+                };
+                return Stream::cons(element, move || filter_rc(&current.tail(), f));
+            }
+        }
+        filter_rc(self, Rc::new(f))
+    }
+
+    /// Pairs up the elements of two streams.
+    ///
+    /// The result ends when either stream ends.
+    pub fn zip<U: Clone + 'static>(&self, other: &Stream<U>) -> Stream<(T, U)> {
+        match (self.head(), other.head()) {
+            (Some(a), Some(b)) => {
+                let (left, right) = (self.clone(), other.clone());
+                Stream::cons((a.clone(), b.clone()), move || {
+                    left.tail().zip(&right.tail())
+                })
+            }
+            _ => Stream::empty(),
+        }
+    }
+
+    /// Returns a List of all of the elements.
+    ///
+    /// The stream must be finite, use `take` first for infinite streams.
+    pub fn to_list(&self) -> List<T> {
+        self.iter().collect()
+    }
+}
+
+impl<T> Clone for Stream<T> {
+    /// Returns a stream sharing all of its Nodes with this one.
+    fn clone(&self) -> Self {
+        Stream {
+            head: self.head.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Stream<T> {
+    fn drop(&mut self) {
+        // Like RcList, free the Nodes that only this stream uses in a loop so
+        // that dropping a long forced stream doesn't recurse.
+        let mut current = self.head.take();
+        while let Some(node) = current {
+            current = match Rc::try_unwrap(node) {
+                Ok(node) => match node.tail.state.into_inner() {
+                    ThunkState::Forced(mut tail) => tail.head.take(),
+                    _ => None,
+                },
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+/// Iterator over clones of the elements of a Stream.
+pub struct Iter<T> {
+    current: Stream<T>,
+}
+
+impl<T: Clone> Iterator for Iter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let element = self.current.head()?.clone();
+        self.current = self.current.tail();
+        Some(element)
+    }
+}
+
+#[cfg(test)]
+fn naturals() -> Stream<u64> {
+    Stream::iterate(0, |n| n + 1)
+}
+
+#[cfg(test)]
+fn fibonacci() -> Stream<u64> {
+    fn from(a: u64, b: u64) -> Stream<u64> {
+        Stream::cons(a, move || from(b, a + b))
+    }
+    from(0, 1)
+}
+
+#[cfg(test)]
+fn to_vec<T: Clone>(stream: &Stream<T>) -> Vec<T> {
+    stream.iter().collect()
+}
+
+#[test]
+fn test_empty() {
+    let stream = Stream::<i32>::empty();
+    assert!(stream.is_empty());
+    assert_eq!(stream.head(), None);
+    assert!(stream.tail().is_empty());
+    assert!(stream.take(3).is_empty());
+    assert!(stream.map(|n| n + 1).is_empty());
+    assert!(stream.to_list().is_empty());
+}
+
+#[test]
+fn test_infinite() {
+    assert_eq!(to_vec(&naturals().take(5)), vec![0, 1, 2, 3, 4]);
+    assert_eq!(
+        to_vec(&fibonacci().take(10)),
+        vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]
+    );
+
+    // The factorials from the slides, as a stream of (n, n!) pairs.
+    let factorials = Stream::iterate((0u64, 1u64), |&(n, f)| (n + 1, f * (n + 1)));
+    for (n, f) in factorials.take(21).iter() {
+        assert_eq!(f, (2..=n).product::<u64>());
+    }
+    // 21! overflows a u64, but it is never computed since it isn't needed.
+    assert_eq!(
+        factorials.map(|&(_, f)| f).take(21).iter().last(),
+        Some(2_432_902_008_176_640_000)
+    );
+}
+
+#[test]
+fn test_combinators() {
+    let evens = naturals().filter(|n| n % 2 == 0);
+    let squares = naturals().map(|n| n * n);
+    assert_eq!(to_vec(&evens.take(4)), vec![0, 2, 4, 6]);
+    assert_eq!(to_vec(&squares.take(4)), vec![0, 1, 4, 9]);
+
+    let pairs = evens.zip(&squares).take(3);
+    assert_eq!(to_vec(&pairs), vec![(0, 0), (2, 1), (4, 4)]);
+    assert_eq!(to_vec(&pairs.zip(&naturals().take(2))).len(), 2);
+
+    let list = naturals().filter(|n| n % 3 == 0).take(3).to_list();
+    assert_eq!(format!("{}", list), "0, 3, 6");
+
+    let finite = Stream::cons(1, || Stream::cons(2, Stream::empty));
+    assert_eq!(to_vec(&finite.filter(|n| n % 2 == 0)), vec![2]);
+    assert!(finite.filter(|n| *n > 5).is_empty());
+    assert_eq!(to_vec(&finite.take(9)), vec![1, 2]);
+}
+
+#[test]
+fn test_memoized() {
+    let calls = Rc::new(std::cell::Cell::new(0));
+    let counter = calls.clone();
+    let stream = naturals().map(move |n| {
+        counter.set(counter.get() + 1);
+        n * 10
+    });
+    let shared = stream.clone();
+
+    assert_eq!(to_vec(&stream.take(5)), vec![0, 10, 20, 30, 40]);
+    assert_eq!(calls.get(), 5);
+    assert_eq!(to_vec(&shared.take(5)), vec![0, 10, 20, 30, 40]);
+    assert_eq!(to_vec(&stream.take(3)), vec![0, 10, 20]);
+    assert_eq!(calls.get(), 5);
+    assert_eq!(to_vec(&shared.take(6)).len(), 6);
+    assert_eq!(calls.get(), 6);
+}
+
+#[test]
+#[should_panic(expected = "depends on itself")]
+fn test_self_dependent_tail() {
+    let cell: Rc<RefCell<Stream<i32>>> = Rc::new(RefCell::new(Stream::empty()));
+    let inner = cell.clone();
+    let stream = Stream::cons(1, move || inner.borrow().tail());
+    *cell.borrow_mut() = stream.clone();
+    stream.tail();
+}
+
+#[test]
+fn test_panicking_tail() {
+    let stream: Stream<i32> = Stream::cons(1, || panic!("no tail"));
+    let force = || std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| stream.tail()));
+    let message = |e: Box<dyn std::any::Any + Send>| match e.downcast::<String>() {
+        Ok(message) => *message,
+        Err(e) => e.downcast::<&str>().unwrap().to_string(),
+    };
+    assert_eq!(message(force().err().unwrap()), "no tail");
+    // Forcing it again doesn't run the closure again, and isn't reported as a
+    // cycle.
+    assert_eq!(
+        message(force().err().unwrap()),
+        "Stream tail panicked when it was forced before"
+    );
+    assert_eq!(stream.head(), Some(&1));
+}
+
+#[test]
+fn test_long_stream() {
+    let stream = naturals();
+    let mut current = stream.clone();
+    for _ in 0..1_000_000 {
+        current = current.tail();
+    }
+    assert_eq!(current.head(), Some(&1_000_000));
+    drop(current);
+    drop(stream);
+
+    assert_eq!(naturals().take(1_000_000).to_list().last(), Some(&999_999));
+}